@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+use smallvec::SmallVec;
+
+use crate::{
+    GameAtlas, AnimationMode, SpriteAnimation,
+    GameTime,
+};
+
+/// Plays one of [`GameAtlas::animations`] on its entity's [`TextureAtlasSprite`], one fixed step at a time.
+#[derive(Component, Clone, Debug)]
+pub struct AnimatedSprite {
+    pub animation: String,
+    pub frame: usize,
+    /// Seconds the current frame has been shown.
+    pub time: f64,
+    pub speed: f64,
+    pub playing: bool,
+    /// Whether an [`AnimationMode::PingPong`] animation is on its way back.
+    pub reversed: bool,
+    /// The frame last written to the sprite, or `None` if the animation hasn't shown its first frame yet.
+    pub shown: Option<usize>,
+}
+
+/// Sent as an animation shows a frame listed in its `events`.
+#[derive(Clone, Debug)]
+pub struct AnimationEvent {
+    pub entity: Entity,
+    pub animation: String,
+    pub event: String,
+}
+
+impl AnimatedSprite {
+    #[inline]
+    pub fn new(animation: impl Into<String>) -> Self {
+        Self {
+            animation: animation.into(),
+            frame: 0,
+            time: 0.,
+            speed: 1.,
+            playing: true,
+            reversed: false,
+            shown: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Starts `animation` over from its first frame, even if it's the one already playing.
+    pub fn play(&mut self, animation: impl Into<String>) {
+        *self = Self {
+            speed: self.speed,
+            ..Self::new(animation)
+        };
+    }
+
+    /// Whether an [`AnimationMode::Once`] animation has reached its last frame.
+    #[inline]
+    pub fn finished(&self) -> bool {
+        !self.playing
+    }
+
+    /// Moves on to the next frame, returning whether one was entered; a one-frame [`AnimationMode::Loop`] enters its
+    /// only frame again.
+    fn advance(&mut self, len: usize, mode: AnimationMode) -> bool {
+        match mode {
+            AnimationMode::Loop => {
+                self.frame = (self.frame + 1) % len;
+                true
+            },
+            AnimationMode::Once => if self.frame + 1 < len {
+                self.frame += 1;
+                true
+            } else {
+                self.playing = false;
+                self.time = 0.;
+                false
+            },
+            AnimationMode::PingPong => if len > 1 {
+                if self.reversed && self.frame == 0 {
+                    self.reversed = false;
+                } else if !self.reversed && self.frame + 1 >= len {
+                    self.reversed = true;
+                }
+
+                if self.reversed { self.frame -= 1 } else { self.frame += 1 }
+                true
+            } else {
+                false
+            },
+        }
+    }
+
+    /// Advances by `delta` seconds, returning every frame entered on the way in order, including ones shown for less
+    /// than the whole step.
+    pub fn step(&mut self, animation: &SpriteAnimation, delta: f64) -> SmallVec<[usize; 2]> {
+        let mut entered = SmallVec::new();
+        let len = animation.frames.len();
+        if len == 0 { return entered };
+
+        self.frame = self.frame.min(len - 1);
+        if !self.playing { return entered };
+
+        self.time += delta * self.speed;
+        // A frame lasts at least a step, so a zero duration can't spin this forever.
+        while self.playing && self.time >= animation.frames[self.frame].duration.max(GameTime::STEP) {
+            self.time -= animation.frames[self.frame].duration.max(GameTime::STEP);
+            if self.advance(len, animation.mode) {
+                entered.push(self.frame);
+            }
+        }
+
+        entered
+    }
+}
+
+pub fn animated_sprite_sys(
+    atlas: Res<GameAtlas>,
+    mut sprites: Query<(Entity, &mut AnimatedSprite, &mut TextureAtlasSprite, &mut Handle<TextureAtlas>)>,
+    mut events: EventWriter<AnimationEvent>,
+) {
+    for (entity, mut anim, mut sprite, mut handle) in &mut sprites {
+        let Some(animation) = atlas.animation(&anim.animation) else { continue };
+
+        // The first frame's event goes out once the animation is first shown; every later one as its frame is entered.
+        let first = anim.shown.is_none().then(|| anim.frame.min(animation.frames.len().saturating_sub(1)));
+        let entered = anim.step(animation, GameTime::STEP);
+        for index in first.into_iter().chain(entered) {
+            if let Some(event) = animation.events.get(&index) {
+                events.send(AnimationEvent {
+                    entity,
+                    animation: anim.animation.clone(),
+                    event: event.clone(),
+                });
+            }
+        }
+
+        let Some(frame) = animation.frames.get(anim.frame) else { continue };
+        if anim.shown == Some(anim.frame) { continue };
+        anim.shown = Some(anim.frame);
+
+        if *handle != frame.sprite.atlas {
+            *handle = frame.sprite.atlas.clone_weak();
+        }
+        sprite.index = frame.sprite.index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        AnimationFrame, AtlasSprite,
+    };
+
+    fn animation(durations: &[f64], mode: AnimationMode) -> SpriteAnimation {
+        SpriteAnimation {
+            frames: durations.iter().enumerate().map(|(index, &duration)| AnimationFrame {
+                sprite: AtlasSprite {
+                    atlas: default(),
+                    index,
+                    rect: default(),
+                },
+                duration,
+            }).collect(),
+            mode,
+            events: default(),
+        }
+    }
+
+    /// Steps `anim` `steps` times, returning the frame shown after each.
+    fn frames(anim: &mut AnimatedSprite, animation: &SpriteAnimation, steps: usize) -> Vec<usize> {
+        (0..steps).map(|_| {
+            anim.step(animation, GameTime::STEP);
+            anim.frame
+        }).collect()
+    }
+
+    #[test]
+    fn loop_wraps() {
+        let animation = animation(&[GameTime::STEP; 3], AnimationMode::Loop);
+        let mut anim = AnimatedSprite::new("loop");
+        assert_eq!(frames(&mut anim, &animation, 5), [1, 2, 0, 1, 2]);
+        assert!(!anim.finished());
+    }
+
+    #[test]
+    fn once_stops_on_last_frame() {
+        let animation = animation(&[GameTime::STEP; 3], AnimationMode::Once);
+        let mut anim = AnimatedSprite::new("once");
+        assert_eq!(frames(&mut anim, &animation, 4), [1, 2, 2, 2]);
+        assert!(anim.finished());
+        assert!(anim.step(&animation, GameTime::STEP).is_empty());
+    }
+
+    #[test]
+    fn ping_pong_turns_around() {
+        let animation = animation(&[GameTime::STEP; 3], AnimationMode::PingPong);
+        let mut anim = AnimatedSprite::new("ping-pong");
+        assert_eq!(frames(&mut anim, &animation, 6), [1, 2, 1, 0, 1, 2]);
+
+        let single = self::animation(&[GameTime::STEP], AnimationMode::PingPong);
+        let mut anim = AnimatedSprite::new("ping-pong");
+        assert_eq!(frames(&mut anim, &single, 2), [0, 0]);
+    }
+
+    #[test]
+    fn step_enters_skipped_frames() {
+        let animation = animation(&[GameTime::STEP; 4], AnimationMode::Loop);
+        let mut anim = AnimatedSprite::new("loop");
+        assert_eq!(anim.step(&animation, GameTime::STEP * 2.).as_slice(), [1, 2]);
+        assert_eq!(anim.frame, 2);
+    }
+
+    #[test]
+    fn single_frame_loop_enters_every_time() {
+        let animation = animation(&[GameTime::STEP], AnimationMode::Loop);
+        let mut anim = AnimatedSprite::new("loop");
+        for _ in 0..3 {
+            assert_eq!(anim.step(&animation, GameTime::STEP).as_slice(), [0]);
+        }
+    }
+
+    #[test]
+    fn events_fire_on_every_frame_entered() {
+        let mut animation = animation(&[GameTime::STEP; 3], AnimationMode::Loop);
+        animation.events.insert(0, "start".into());
+        animation.events.insert(1, "middle".into());
+
+        let mut world = World::new();
+        world.init_resource::<Events<AnimationEvent>>();
+        world.insert_resource(GameAtlas {
+            pages: default(),
+            sprites: default(),
+            animations: [("anim".to_string(), animation)].into_iter().collect(),
+            missing: AtlasSprite {
+                atlas: default(),
+                index: 0,
+                rect: default(),
+            },
+            problems: default(),
+        });
+
+        world.spawn((AnimatedSprite::new("anim").with_speed(2.), TextureAtlasSprite::default(), Handle::<TextureAtlas>::default()));
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(animated_sprite_sys);
+
+        let mut sent = Vec::new();
+        for _ in 0..3 {
+            schedule.run(&mut world);
+            let mut events = world.resource_mut::<Events<AnimationEvent>>();
+            sent.extend(events.drain().map(|event| event.event));
+        }
+
+        // At double speed each step enters two frames, one of which is never shown.
+        assert_eq!(sent, ["start", "middle", "start", "middle", "start"]);
+    }
+}
@@ -1,21 +1,73 @@
 use bevy::{
     prelude::*,
+    asset::{
+        AssetLoader, AssetPath, LoadContext, LoadedAsset,
+    },
     ecs::system::SystemState,
+    reflect::TypeUuid,
+    utils::{
+        BoxedFuture, HashMap,
+    },
     render::{
         render_resource::{
             SamplerDescriptor, AddressMode,
         },
-        texture::ImageSampler,
+        texture::{
+            ImageSampler, DEFAULT_IMAGE_HANDLE,
+        },
     },
 };
 
 use bevy_asset_loader::prelude::*;
 use bevy_ecs_ldtk::prelude::*;
+use iyes_progress::{
+    prelude::*,
+    TrackedProgressSet,
+};
+use serde::{
+    Serialize, Deserialize,
+};
+
+use crate::GameStates;
+#[cfg(feature = "dev")]
+use crate::atlas_watch_sys;
 
-use std::mem;
+use std::{
+    collections::BTreeMap,
+    path::Path,
+};
 
+/// Pixels `cix-pack` pads every sprite with, so linear filtering doesn't bleed neighbours in.
 pub const ATLAS_PAD: (usize, usize) = (4, 4);
 
+pub struct AssetsPlugin;
+impl Plugin for AssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_state::<GameStates>()
+            .add_loading_state(LoadingState::new(GameStates::Loading))
+            .add_collection_to_loading_state::<_, LdtkWorld>(GameStates::Loading)
+            .add_collection_to_loading_state::<_, BackgroundImages>(GameStates::Loading)
+            .add_asset::<AtlasLayout>()
+            .init_asset_loader::<AtlasLayoutLoader>()
+            .add_collection_to_loading_state::<_, AtlasAssets>(GameStates::Loading)
+            .add_system(atlas_build_sys
+                .in_set(TrackedProgressSet)
+                .run_if(in_state(GameStates::Loading))
+            )
+
+            .add_plugin(ProgressPlugin::new(GameStates::Loading).continue_to(GameStates::MainMenu));
+
+        #[cfg(feature = "dev")]
+        app
+            .add_system(atlas_watch_sys)
+            .add_system(atlas_reload_sys
+                .run_if(resource_exists::<GameAtlas>())
+                .run_if(on_event::<AssetEvent<AtlasLayout>>())
+            );
+    }
+}
+
 #[derive(AssetCollection, Resource)]
 pub struct Fonts {
     #[asset(path = "fonts/font.ttf")]
@@ -36,76 +88,234 @@ pub struct BackgroundImages {
     pub front: Handle<Image>,
 }
 
+/// The atlas `cix-pack` packed out of `sprites/`. The pages are loaded as a folder too, so loading waits for them.
 #[derive(AssetCollection, Resource)]
-pub struct GenericSprites {
-    #[asset(path = "sprites/generic/circle.png")]
-    pub circle: Handle<Image>,
-    #[asset(path = "sprites/generic/square.png")]
-    pub square: Handle<Image>,
-    #[asset(path = "sprites/generic/triangle.png")]
-    pub triangle: Handle<Image>,
+pub struct AtlasAssets {
+    #[asset(path = "atlas/atlas.ron")]
+    pub layout: Handle<AtlasLayout>,
+    #[asset(path = "atlas/pages", collection(typed))]
+    pub pages: Vec<Handle<Image>>,
 }
 
-#[derive(AssetCollection, Resource)]
-pub struct EnvironmentSprites {
-    #[asset(path = "sprites/environment/gate.png")]
-    pub gate: Handle<Image>,
-    #[asset(path = "sprites/environment/petal.png")]
-    pub petal: Handle<Image>,
+/// Where `cix-pack` put every sprite, written next to the pages it packed.
+#[derive(TypeUuid, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[uuid = "2f5e0c7a-93b1-4e8d-a6f4-0b7d1c3e5a92"]
+pub struct AtlasLayout {
+    pub pages: Vec<AtlasLayoutPage>,
+    pub sprites: BTreeMap<String, AtlasLayoutSprite>,
+    /// Read from the `*.anim.ron` files under `sprites/`, named like the sprites are.
+    #[serde(default)]
+    pub animations: BTreeMap<String, AtlasLayoutAnimation>,
+    /// The page images, in the same order as [`Self::pages`]. Filled in by [`AtlasLayoutLoader`].
+    #[serde(skip)]
+    pub images: Vec<Handle<Image>>,
 }
 
-#[derive(AssetCollection, Resource)]
-pub struct CixSprites {
-    #[asset(path = "sprites/cix/head.png")]
-    pub head: Handle<Image>,
-    #[asset(path = "sprites/cix/eye.png")]
-    pub eye: Handle<Image>,
-
-    #[asset(path = "sprites/cix/red-collar.png")]
-    pub red_collar: Handle<Image>,
-    #[asset(path = "sprites/cix/blue-cape.png")]
-    pub blue_cape: Handle<Image>,
-    #[asset(path = "sprites/cix/pink-collar.png")]
-    pub pink_collar: Handle<Image>,
-    #[asset(path = "sprites/cix/red-scarf.png")]
-    pub red_scarf: Handle<Image>,
-    #[asset(path = "sprites/cix/pink-scarf.png")]
-    pub pink_scarf: Handle<Image>,
-
-    #[asset(path = "sprites/cix/arm-front-upper.png")]
-    pub arm_front_upper: Handle<Image>,
-    #[asset(path = "sprites/cix/arm-front-lower.png")]
-    pub arm_front_lower: Handle<Image>,
-    #[asset(path = "sprites/cix/arm-back-upper.png")]
-    pub arm_back_upper: Handle<Image>,
-    #[asset(path = "sprites/cix/arm-back-lower.png")]
-    pub arm_back_lower: Handle<Image>,
-
-    #[asset(path = "sprites/cix/laser.png")]
-    pub laser: Handle<Image>,
-    #[asset(path = "sprites/cix/laser-end.png")]
-    pub laser_end: Handle<Image>,
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AtlasLayoutPage {
+    /// Relative to the layout file.
+    pub file: String,
+    pub size: [u32; 2],
 }
 
-#[derive(AssetCollection, Resource)]
-pub struct StaticEnemySprites {
-    #[asset(path = "sprites/enemies/static/barrier.png")]
-    pub barrier: Handle<Image>,
-    #[asset(path = "sprites/enemies/static/gear.png")]
-    pub gear: Handle<Image>,
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AtlasLayoutSprite {
+    pub page: usize,
+    /// `[x, y, width, height]` in pixels, without the padding.
+    pub rect: [u32; 4],
+}
+
+/// A sprite-sheet animation, exactly as written in its `*.anim.ron` file.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AtlasLayoutAnimation {
+    pub frames: Vec<AtlasLayoutFrame>,
+    #[serde(default)]
+    pub mode: AnimationMode,
+    /// [`AnimationEvent`](crate::AnimationEvent)s sent as the frame at each index is shown.
+    #[serde(default)]
+    pub events: BTreeMap<usize, String>,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AtlasLayoutFrame {
+    /// Any sprite in the atlas, usually one of a folder of numbered frames next to the animation.
+    pub sprite: String,
+    /// In seconds.
+    pub duration: f32,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum AnimationMode {
+    #[default]
+    Loop,
+    /// Stops on the last frame.
+    Once,
+    /// Plays back and forth.
+    PingPong,
+}
+
+#[derive(Default)]
+pub struct AtlasLayoutLoader;
+impl AssetLoader for AtlasLayoutLoader {
+    fn load<'a>(&'a self, bytes: &'a [u8], load_context: &'a mut LoadContext) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut layout = ron::de::from_bytes::<AtlasLayout>(bytes)?;
+            let dir = load_context.path().parent().unwrap_or(Path::new("")).to_path_buf();
+
+            let paths = layout.pages.iter()
+                .map(|page| AssetPath::from(dir.join(&page.file)))
+                .collect::<Vec<_>>();
+            layout.images = paths.iter()
+                .map(|path| load_context.get_handle(path.clone()))
+                .collect();
+
+            load_context.set_default_asset(LoadedAsset::new(layout).with_dependencies(paths));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.ron"]
+    }
+}
+
+/// One sprite in [`GameAtlas`]: the page it's on, its index there, and its rectangle without the padding.
+#[derive(Clone, Debug)]
+pub struct AtlasSprite {
+    pub atlas: Handle<TextureAtlas>,
+    pub index: usize,
+    pub rect: Rect,
+}
+
+impl AtlasSprite {
+    #[inline]
+    pub fn size(&self) -> Vec2 {
+        self.rect.size()
+    }
+}
+
+/// Declares a resource of [`AtlasSprite`]s resolved from [`GameAtlas`] once, so spawning them doesn't look anything up.
+macro_rules! sprite_set {
+    ($name:ident { $($field:ident: $path:literal,)* }) => {
+        #[derive(Resource, Clone)]
+        pub struct $name {
+            $(pub $field: AtlasSprite,)*
+        }
+
+        impl FromWorld for $name {
+            fn from_world(world: &mut World) -> Self {
+                let mut atlas = world.resource_mut::<GameAtlas>();
+                let mut resolve = |name: &str| match atlas.get(name) {
+                    Some(sprite) => sprite.clone(),
+                    None => {
+                        atlas.problems.push(format!("No sprite named {name}; was the atlas repacked with `cix-pack`?"));
+                        atlas.missing.clone()
+                    },
+                };
+
+                Self {
+                    $($field: resolve($path),)*
+                }
+            }
+        }
+    };
+}
+
+sprite_set!(GenericSprites {
+    circle: "generic/circle",
+    square: "generic/square",
+    triangle: "generic/triangle",
+});
+
+sprite_set!(EnvironmentSprites {
+    gate: "environment/gate",
+    petal: "environment/petal",
+});
+
+sprite_set!(CixSprites {
+    head: "cix/head",
+    eye: "cix/eye",
+
+    red_collar: "cix/red-collar",
+    blue_cape: "cix/blue-cape",
+    pink_collar: "cix/pink-collar",
+    red_scarf: "cix/red-scarf",
+    pink_scarf: "cix/pink-scarf",
+
+    arm_front_upper: "cix/arm-front-upper",
+    arm_front_lower: "cix/arm-front-lower",
+    arm_back_upper: "cix/arm-back-upper",
+    arm_back_lower: "cix/arm-back-lower",
+
+    laser: "cix/laser",
+    laser_end: "cix/laser-end",
+});
+
+sprite_set!(StaticEnemySprites {
+    barrier: "enemies/static/barrier",
+    gear: "enemies/static/gear",
+});
+
+/// An [`AtlasLayoutAnimation`] with its frames looked up, played by [`AnimatedSprite`](crate::AnimatedSprite).
+#[derive(Clone, Debug)]
+pub struct SpriteAnimation {
+    pub frames: Vec<AnimationFrame>,
+    pub mode: AnimationMode,
+    pub events: BTreeMap<usize, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AnimationFrame {
+    pub sprite: AtlasSprite,
+    pub duration: f64,
+}
+
+/// Every sprite `cix-pack` packed, named by its path under `sprites/` without the extension, e.g. `cix/head`.
+#[derive(Resource)]
+pub struct GameAtlas {
+    pub pages: Vec<Handle<TextureAtlas>>,
+    pub sprites: HashMap<String, AtlasSprite>,
+    pub animations: HashMap<String, SpriteAnimation>,
+    /// A plain white square on its own page, standing in for sprites that aren't there.
+    pub missing: AtlasSprite,
+    /// What's wrong with the layout or the sprites looked up in it, shown on screen rather than crashing.
+    pub problems: Vec<String>,
+}
+
+impl GameAtlas {
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&AtlasSprite> {
+        self.sprites.get(name)
+    }
+
+    #[inline]
+    pub fn animation(&self, name: &str) -> Option<&SpriteAnimation> {
+        self.animations.get(name)
+    }
+
+    /// Falls back to [`Self::missing`] if there's no such sprite.
+    #[inline]
+    pub fn sprite(&self, name: &str) -> &AtlasSprite {
+        self.get(name).unwrap_or(&self.missing)
+    }
+
+    /// `sprites/cix/head.png` becomes `cix/head`.
+    pub fn sprite_name(path: &Path) -> String {
+        path.strip_prefix("sprites").unwrap_or(path)
+            .with_extension("")
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
 }
 
-#[derive(Resource, Deref)]
-pub struct GameAtlas(pub Handle<TextureAtlas>);
 impl FromWorld for GameAtlas {
     fn from_world(world: &mut World) -> Self {
-        let (server, bg, generic_sprites, env_sprites, cix_sprites, enemy_static_sprites, mut images, mut atlases) = SystemState::<(
-            Res<AssetServer>,
+        let (bg, assets, layouts, mut images, mut atlases) = SystemState::<(
             ResMut<BackgroundImages>,
-            ResMut<GenericSprites>,
-            ResMut<EnvironmentSprites>,
-            ResMut<CixSprites>,
-            ResMut<StaticEnemySprites>,
+            Res<AtlasAssets>,
+            Res<Assets<AtlasLayout>>,
             ResMut<Assets<Image>>,
             ResMut<Assets<TextureAtlas>>,
         )>::new(world).get_mut(world);
@@ -120,104 +330,122 @@ impl FromWorld for GameAtlas {
             });
         }
 
-        let generic_sprites = generic_sprites.into_inner();
-        let env_sprites = env_sprites.into_inner();
-        let cix_sprites = cix_sprites.into_inner();
-        let enemy_static_sprites = enemy_static_sprites.into_inner();
-
-        let mut builder = TextureAtlasBuilder::default();
-
-        let (pad_x, pad_y) = ATLAS_PAD;
-        for handle in [
-            &mut generic_sprites.circle,
-            &mut generic_sprites.square,
-            &mut generic_sprites.triangle,
-
-            &mut env_sprites.gate,
-            &mut env_sprites.petal,
-
-            &mut cix_sprites.head,
-            &mut cix_sprites.eye,
-
-            &mut cix_sprites.red_collar,
-            &mut cix_sprites.blue_cape,
-            &mut cix_sprites.pink_collar,
-            &mut cix_sprites.red_scarf,
-            &mut cix_sprites.pink_scarf,
-
-            &mut cix_sprites.arm_front_upper,
-            &mut cix_sprites.arm_front_lower,
-            &mut cix_sprites.arm_back_upper,
-            &mut cix_sprites.arm_back_lower,
-
-            &mut cix_sprites.laser,
-            &mut cix_sprites.laser_end,
-
-            &mut enemy_static_sprites.barrier,
-            &mut enemy_static_sprites.gear,
-        ] {
-            let handle = mem::replace(handle, handle.clone_weak());
-            let image = images.get_mut(&handle).unwrap_or_else(|| panic!("{:?} is deallocated", server.get_handle_path(&handle)));
-
-            let pixel_size = 4 * mem::size_of::<u8>();
-            let (width, height) = {
-                let size = image.texture_descriptor.size;
-                (size.width as usize, size.height as usize)
+        let layout = layouts.get(&assets.layout).expect("Atlas layout deallocated");
+        let mut pages = layout.pages.iter().zip(&layout.images)
+            .map(|(page, image)| atlases.add(TextureAtlas::new_empty(
+                image.clone(),
+                Vec2::new(page.size[0] as f32, page.size[1] as f32),
+            )))
+            .collect::<Vec<_>>();
+
+        let mut problems = Vec::new();
+        let mut sprites = HashMap::default();
+        for (name, sprite) in &layout.sprites {
+            let Some(page) = pages.get(sprite.page) else {
+                problems.push(format!("Sprite {name} is on page {}, but the atlas has {}", sprite.page, pages.len()));
+                continue;
             };
-
-            let (canvas_width, canvas_height) = (width + pad_x * 2, height + pad_y * 2);
-
-            let canvas = &mut image.data;
-            let frame = mem::replace(canvas, vec![0; canvas_width * canvas_height * pixel_size]);
-
-            let row_len = width * pixel_size;
-            for y in pad_y..(canvas_height - pad_y) {
-                let frame_row = (y - pad_y) * row_len;
-                let canvas_row = (y * canvas_width + pad_x) * pixel_size;
-                canvas[canvas_row..(canvas_row + row_len)].copy_from_slice(&frame[frame_row..(frame_row + row_len)]);
-            }
-
-            {
-                let size = &mut image.texture_descriptor.size;
-                size.width = canvas_width as u32;
-                size.height = canvas_height as u32;
-            }
-
-            builder.add_texture(handle, image);
+            let atlas = atlases.get_mut(page).unwrap();
+
+            let [x, y, width, height] = sprite.rect;
+            let rect = Rect::new(x as f32, y as f32, (x + width) as f32, (y + height) as f32);
+            sprites.insert(name.clone(), AtlasSprite {
+                atlas: page.clone_weak(),
+                index: atlas.add_texture(rect),
+                rect,
+            });
         }
 
-        let mut atlas = match builder.finish(&mut images) {
-            Ok(atlas) => atlas,
-            Err(e) => panic!("Couldn't build texture atlas: {e}"),
+        let missing = {
+            let mut atlas = TextureAtlas::new_empty(DEFAULT_IMAGE_HANDLE.typed(), Vec2::ONE);
+            let rect = Rect::new(0., 0., 1., 1.);
+            let index = atlas.add_texture(rect);
+
+            let page = atlases.add(atlas);
+            let sprite = AtlasSprite { atlas: page.clone_weak(), index, rect, };
+            pages.push(page);
+            sprite
         };
 
-        let pad = Vec2::new(pad_x as f32, pad_y as f32);
-        for rect in &mut atlas.textures {
-            rect.min += pad;
-            rect.max -= pad;
+        let mut animations = HashMap::default();
+        for (name, animation) in &layout.animations {
+            let frames = animation.frames.iter()
+                .map(|frame| AnimationFrame {
+                    sprite: sprites.get(&frame.sprite).cloned().unwrap_or_else(|| {
+                        problems.push(format!("Animation {name} shows {}, which isn't in the atlas", frame.sprite));
+                        missing.clone()
+                    }),
+                    duration: frame.duration as f64,
+                })
+                .collect();
+
+            animations.insert(name.clone(), SpriteAnimation {
+                frames,
+                mode: animation.mode,
+                events: animation.events.clone(),
+            });
         }
 
-        Self(atlases.add(atlas))
+        Self { pages, sprites, animations, missing, problems, }
     }
 }
 
-impl GameAtlas {
-    #[inline]
-    pub fn rect(&self, atlases: &Assets<TextureAtlas>, sprite: &Handle<Image>) -> Rect {
-        let atlas = atlases.get(self).expect("Texture atlas deallocated");
-        atlas.textures[atlas.get_texture_index(sprite).expect("Invalid texture atlas sprite")]
+/// Resolves every sprite set from [`GameAtlas`], replacing any already there.
+fn insert_sprite_sets(world: &mut World) {
+    let generic = GenericSprites::from_world(world);
+    let environment = EnvironmentSprites::from_world(world);
+    let cix = CixSprites::from_world(world);
+    let static_enemy = StaticEnemySprites::from_world(world);
+    world.insert_resource(generic);
+    world.insert_resource(environment);
+    world.insert_resource(cix);
+    world.insert_resource(static_enemy);
+}
+
+/// Builds [`GameAtlas`] and the sprite sets as soon as their assets are in, counting as one step of the loading
+/// progress until then.
+pub fn atlas_build_sys(world: &mut World) {
+    if world.contains_resource::<GameAtlas>() { return };
+
+    let ready = world.contains_resource::<AtlasAssets>() && world.contains_resource::<BackgroundImages>();
+    if ready {
+        let atlas = GameAtlas::from_world(world);
+        world.insert_resource(atlas);
+        insert_sprite_sets(world);
     }
 
-    #[inline]
-    pub fn index(&self, atlases: &Assets<TextureAtlas>, sprite: &Handle<Image>) -> usize {
-        let atlas = atlases.get(self).expect("Texture atlas deallocated");
-        atlas.get_texture_index(sprite).expect("Invalid texture atlas sprite")
+    let Some(mut counter) = world.get_resource_mut::<ProgressCounter>() else { return };
+    if ready {
+        counter.persist_progress(Progress { done: 1, total: 1 });
+    } else {
+        counter.manually_track(Progress { done: 0, total: 1 });
     }
+}
 
-    #[inline]
-    pub fn rect_index(&self, atlases: &Assets<TextureAtlas>, sprite: &Handle<Image>) -> (Rect, usize) {
-        let atlas = atlases.get(self).expect("Texture atlas deallocated");
-        let index = atlas.get_texture_index(sprite).expect("Invalid texture atlas sprite");
-        (atlas.textures[index], index)
+/// Rebuilds [`GameAtlas`] and the sprite sets once [`atlas_watch_sys`] or `cix-pack` rewrites the layout, and points
+/// every sprite already spawned at where it went. Changed pixels alone need nothing, as the pages reload by themselves.
+#[cfg(feature = "dev")]
+pub fn atlas_reload_sys(world: &mut World) {
+    let old = world.remove_resource::<GameAtlas>().unwrap();
+    let atlas = GameAtlas::from_world(world);
+
+    let names = old.sprites.iter()
+        .map(|(name, sprite)| ((sprite.atlas.id(), sprite.index), name.as_str()))
+        .collect::<HashMap<_, _>>();
+
+    let mut sprites = world.query::<(&mut Handle<TextureAtlas>, &mut TextureAtlasSprite)>();
+    for (mut handle, mut sprite) in sprites.iter_mut(world) {
+        let new = if handle.id() == old.missing.atlas.id() {
+            &atlas.missing
+        } else {
+            let Some(new) = names.get(&(handle.id(), sprite.index)).and_then(|&name| atlas.get(name)) else { continue };
+            new
+        };
+
+        *handle = new.atlas.clone_weak();
+        sprite.index = new.index;
     }
+
+    world.insert_resource(atlas);
+    insert_sprite_sets(world);
 }
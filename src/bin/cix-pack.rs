@@ -0,0 +1,43 @@
+//! Packs every PNG under `sprites/` into pages under `assets/atlas/pages/`, and writes where each sprite went to
+//! `assets/atlas/atlas.ron`. Run it after adding or changing a sprite:
+//!
+//! ```text
+//! cargo run --no-default-features --bin cix-pack
+//! ```
+//!
+//! Passing `--check` writes nothing and fails if the packed atlas is out of date instead. Builds with the `dev` feature
+//! run the same packing by themselves whenever `sprites/` changes.
+//!
+//! Sprites are padded by [`ATLAS_PAD`](cix::ATLAS_PAD) with their own border pixels extruded outward, so linear filtering at fractional
+//! scales samples the sprite's edge instead of dark transparent texels. Sprites listed in `sprites/padding.ron` as
+//! `Transparent` get plain transparent borders instead.
+//!
+//! Every `*.anim.ron` file under `sprites/` is an [`AtlasLayoutAnimation`](cix::AtlasLayoutAnimation) over sprites in the atlas, e.g.
+//! `sprites/environment/gate-open.anim.ron` over `sprites/environment/gate-open/0.png` and so on. They're checked and
+//! copied into the layout under their name without `.anim`, so the game doesn't have to read them separately.
+
+use cix::{
+    pack_atlas, write_atlas, compare_atlas,
+};
+
+use std::{
+    env,
+    process::ExitCode,
+};
+
+fn main() -> ExitCode {
+    let check = env::args().skip(1).any(|arg| arg == "--check");
+    let result = pack_atlas().and_then(|(layout, pages)| if check {
+        compare_atlas(&layout, &pages)
+    } else {
+        write_atlas(&layout, &pages).map(|()| println!("Packed {} sprites into {} pages", layout.sprites.len(), layout.pages.len()))
+    });
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        },
+    }
+}
@@ -0,0 +1,85 @@
+//! Runs scripted playthroughs without a window or a renderer, reporting how each one ended.
+//!
+//! ```text
+//! cargo run --no-default-features --bin cix-sim -- scripts/*.sim
+//! ```
+//!
+//! Passing `--trace` prints Cix's position every frame, which helps when writing new scripts. Replays recorded
+//! with `cix --record` run the same way.
+
+use bevy::prelude::*;
+
+use cix::{
+    AssetsPlugin, WorldPlugin, CixPlugin, EnemiesPlugin,
+    Cix, CixGrounded,
+    HeadlessPlugins, SimPlugin, SimScript, SimState,
+    CixReplay,
+};
+
+use std::{
+    env, fs,
+    process::ExitCode,
+};
+
+fn main() -> ExitCode {
+    if cfg!(feature = "graphics") {
+        eprintln!("`cix-sim` needs the render app to be absent; build it with `--no-default-features`.");
+        return ExitCode::from(2);
+    }
+
+    let (flags, paths) = env::args().skip(1).partition::<Vec<_>, _>(|arg| arg.starts_with("--"));
+    let trace = flags.iter().any(|flag| flag == "--trace");
+
+    if paths.is_empty() {
+        eprintln!("Usage: cix-sim [--trace] <script>...");
+        return ExitCode::from(2);
+    }
+
+    let mut failed = false;
+    for path in paths {
+        let script = match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|source| if source.starts_with(CixReplay::HEADER) {
+            CixReplay::parse(&source).map(|replay| replay.to_script())
+        } else {
+            SimScript::parse(&source)
+        }) {
+            Ok(script) => script,
+            Err(e) => {
+                eprintln!("{path}: {e}");
+                failed = true;
+                continue;
+            },
+        };
+
+        let mut app = App::new();
+        app
+            .add_plugins(HeadlessPlugins)
+            .add_plugin(AssetsPlugin)
+            .add_plugin(WorldPlugin)
+            .add_plugin(CixPlugin)
+            .add_plugin(EnemiesPlugin)
+            .add_plugin(SimPlugin { script });
+
+        app.setup();
+        let (outcome, frames) = loop {
+            app.update();
+            if trace {
+                let frame = app.world.resource::<SimState>().frame.unwrap_or(0);
+                let mut cix = app.world.query_filtered::<(&GlobalTransform, &CixGrounded), With<Cix>>();
+                if let Ok((&global_trns, &grounded)) = cix.get_single(&app.world) {
+                    let pos = global_trns.translation();
+                    println!("{frame}: {:.1} {:.1}{}", pos.x, pos.y, if *grounded { " grounded" } else { "" });
+                }
+            }
+
+            let state = app.world.resource::<SimState>();
+            if let Some(ref outcome) = state.outcome {
+                break (outcome.clone(), state.frame.unwrap_or(0));
+            }
+        };
+
+        println!("{path}: {outcome} after {frames} frames");
+        failed |= !outcome.completed();
+    }
+
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
@@ -2,21 +2,50 @@ use bevy::{
     prelude::*,
     core_pipeline::bloom::BloomSettings,
     math::DVec2,
+    render::camera::CameraUpdateSystem,
     window::PrimaryWindow,
 };
 use leafwing_input_manager::prelude::*;
+use serde::{
+    Serialize, Deserialize,
+};
+
+use crate::Settings;
 
 pub const CAMERA_VIEW: DVec2 = DVec2::new(1440., 900.);
 
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+#[derive(Actionlike, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
 pub enum CameraAction {
     ToggleBloom,
+    /// Only does anything with the [`DebugPlugin`](crate::DebugPlugin) built in.
+    ToggleDebug,
 }
 
 #[derive(Resource, Deref, DerefMut, Copy, Clone)]
 pub struct CameraPos(pub Vec2);
 
-pub fn camera_spawn_sys(mut commands: Commands) {
+pub struct CameraPlugin;
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugin(InputManagerPlugin::<CameraAction>::default())
+
+            .add_startup_systems((
+                camera_spawn_sys,
+                camera_viewport_sys
+                    .in_base_set(StartupSet::PostStartup)
+                    .before(CameraUpdateSystem),
+            ))
+            .add_system(camera_viewport_sys
+                .in_base_set(CoreSet::PostUpdate)
+                .before(CameraUpdateSystem)
+            )
+            .add_system(camera_toggle_bloom_sys)
+            .add_system(camera_bloom_sys.run_if(resource_changed::<Settings>()));
+    }
+}
+
+pub fn camera_spawn_sys(mut commands: Commands, settings: Res<Settings>) {
     commands.spawn((
         Camera2dBundle {
             camera: Camera {
@@ -25,34 +54,36 @@ pub fn camera_spawn_sys(mut commands: Commands) {
             },
             ..default()
         },
-        BloomSettings {
-            intensity: 0.4,
-            ..BloomSettings::NATURAL
-        },
         InputManagerBundle {
             action_state: default(),
-            input_map: InputMap::default()
-                .insert(KeyCode::F12, CameraAction::ToggleBloom)
-                .build(),
+            input_map: settings.bindings.camera.clone(),
         },
     ));
 }
 
 pub fn camera_toggle_bloom_sys(
-    mut commands: Commands,
-    camera: Query<(Entity, &ActionState<CameraAction>, Option<&BloomSettings>), With<Camera>>,
+    mut settings: ResMut<Settings>,
+    camera: Query<&ActionState<CameraAction>, With<Camera>>,
 ) {
-    let (e, input, has_bloom) = camera.single();
+    let input = camera.single();
     if input.just_pressed(CameraAction::ToggleBloom) {
-        let mut cmd = commands.entity(e);
-        if has_bloom.is_some() {
-            cmd.remove::<BloomSettings>();
-        } else {
-            cmd.insert(BloomSettings {
-                intensity: 0.4,
-                ..BloomSettings::NATURAL
-            });
-        }
+        settings.graphics.bloom = !settings.graphics.bloom;
+    }
+}
+
+pub fn camera_bloom_sys(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    camera: Query<Entity, With<Camera>>,
+) {
+    let mut cmd = commands.entity(camera.single());
+    if settings.graphics.bloom {
+        cmd.insert(BloomSettings {
+            intensity: settings.graphics.bloom_intensity,
+            ..BloomSettings::NATURAL
+        });
+    } else {
+        cmd.remove::<BloomSettings>();
     }
 }
 
@@ -3,9 +3,10 @@ use smallvec::SmallVec;
 
 use crate::{
     ext::*,
-    CixSprites, GameAtlas,
+    CixSprites, AtlasSprite,
     CixDirection,
     CixAttire,
+    Easing,
 };
 
 #[derive(Component, Copy, Clone, Eq, PartialEq)]
@@ -29,7 +30,7 @@ impl CixArm {
     pub const TARGET_POINT: Vec2 = Vec2::new(1.5, -26.);
 
     #[inline]
-    pub fn sprites(self, sprites: &CixSprites) -> (&Handle<Image>, &Handle<Image>) {
+    pub fn sprites(self, sprites: &CixSprites) -> (&AtlasSprite, &AtlasSprite) {
         use CixArm::*;
         match self {
             Front => (&sprites.arm_front_upper, &sprites.arm_front_lower),
@@ -66,16 +67,14 @@ impl CixArm {
 }
 
 pub fn cix_update_arm_sys(
-    time: Res<Time>,
     cix: Query<&CixDirection>,
     mut arm: Query<(&CixArm, &Children, &CixArmTarget, &mut Transform)>,
     mut arms: Query<(&mut Transform, &mut TextureAtlasSprite), Without<CixArm>>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<CixSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<CixSprites>,
 ) {
     let Ok(&dir) = cix.get_single() else { return };
     let mut prog = dir.progress;
-    prog = prog * prog * (3. - 2. * prog);
+    prog = Easing::SmoothStep.ease(prog);
 
     let size_prog = 1. - (0.5 - (prog - 0.5).abs()) * 2. * CixAttire::ROTATE_SHRINK;
     let anchor_prog = (prog * 2. - 1.) * if dir.right { 1. } else { -1. };
@@ -114,18 +113,17 @@ pub fn cix_update_arm_sys(
             arms.into_inner().unwrap()
         });
 
-        let delta = speed * (time.delta_seconds() * 60.).min(1.);
         let cur_joint = lower_trns.translation.truncate();
         let angle_diff = Vec2::X
             .angle_between(cur_joint)
             .angle_dist_avoid(Vec2::X.angle_between(target_joint), if p >= 0.5 { f32::PI } else { 0. });
-        let joint = cur_joint.rotate(Vec2::from_angle(angle_diff * delta));
+        let joint = cur_joint.rotate(Vec2::from_angle(angle_diff * speed));
 
         let upper_angle = Vec2::X.angle_between(joint);
         let lower_angle = {
             let from = lower_trns.rotation.to_axis_angle().1;
             let to = Vec2::X.angle_between(end - target_joint);
-            let dist = from.angle_dist_avoid(to, upper_angle + std::f32::consts::PI) * delta;
+            let dist = from.angle_dist_avoid(to, upper_angle + std::f32::consts::PI) * speed;
 
             (from + dist).angle_wrap()
         };
@@ -134,9 +132,9 @@ pub fn cix_update_arm_sys(
         lower_trns.translation = joint.extend(0.);
         lower_trns.rotation = Quat::from_axis_angle(Vec3::Z, lower_angle);
 
-        let (upper_handle, lower_handle) = arm.sprites(&sprites);
-        for (handle, sprite) in [(upper_handle, &mut upper_sprite), (lower_handle, &mut lower_sprite)] {
-            let sprite_size = atlas.rect(&atlases, handle).size();
+        let (upper, lower) = arm.sprites(&sprites);
+        for (atlas_sprite, sprite) in [(upper, &mut upper_sprite), (lower, &mut lower_sprite)] {
+            let sprite_size = atlas_sprite.size();
             sprite.custom_size = Some(Vec2::new(
                 sprite_size.x,
                 sprite_size.y * size_prog,
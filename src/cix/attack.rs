@@ -6,16 +6,18 @@ use bevy_rapier2d::prelude::*;
 use rand::{
     prelude::*,
     distributions::Uniform,
+    rngs::StdRng,
 };
 
 use crate::{
     ext::*,
     GROUP_CIX, GROUP_BULLET, GROUP_STOP_PIERCE,
-    CixSprites, GameAtlas,
+    CixSprites,
     Cix, CixDirection,
     CixArm, CixArmTarget,
     CixAction, CixActState,
-    Health, Timed,
+    Health, Timed, Easing,
+    GameTime, GameRng,
 };
 
 use std::ops::RangeInclusive as RangeIncl;
@@ -56,25 +58,26 @@ pub struct CixLaserChargeParticle {
 
 pub fn cix_attack_sys(
     mut commands: Commands,
-    context: Res<RapierContext>, time: Res<Time>,
+    context: Res<RapierContext>, time: Res<GameTime>, mut rng: ResMut<GameRng>,
     mut cix: Query<(&CixActState, &CixAttack, &mut CixAttackState, &CixDirection, &GlobalTransform)>,
     mut charge_particles: Query<(&mut CixLaserChargeParticle, &mut Transform, &mut TextureAtlasSprite)>,
     mut arms: Query<(&mut CixArmTarget, &GlobalTransform)>,
     mut enemies: Query<&mut Health, Without<Cix>>, groups: Query<&CollisionGroups>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<CixSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<CixSprites>,
 ) {
     let Ok((input, &attack, mut state, &dir, &global_trns)) = cix.get_single_mut() else { return };
     if input.pressed(CixAction::Attack) {
-        let reset_charge = |charge_particles: &mut Query<(&mut CixLaserChargeParticle, &mut Transform, &mut TextureAtlasSprite)>| {
-            let mut rng = thread_rng();
+        let reset_charge = |
+            rng: &mut StdRng,
+            charge_particles: &mut Query<(&mut CixLaserChargeParticle, &mut Transform, &mut TextureAtlasSprite)>,
+        | {
             let angle = Uniform::from(0f32..(360f32).to_radians());
             let dist = Uniform::from(48f32..=96f32);
             let radius = Uniform::from((CixLaser::WIDTH / 12.)..=(CixLaser::WIDTH / 2.));
 
             for (mut particle, mut trns, mut sprite) in charge_particles {
-                particle.radius = radius.sample(&mut rng);
-                particle.offset = Vec2::from_angle(angle.sample(&mut rng)) * dist.sample(&mut rng);
+                particle.radius = radius.sample(rng);
+                particle.offset = Vec2::from_angle(angle.sample(rng)) * dist.sample(rng);
                 trns.translation = particle.offset.extend(10.);
                 sprite.color = *CixLaser::COLOR.end();
                 sprite.custom_size = Some(Vec2::splat(0.));
@@ -83,11 +86,11 @@ pub fn cix_attack_sys(
 
         if input.just_pressed(CixAction::Attack) {
             state.shoot = attack.init;
-            reset_charge(&mut charge_particles);
+            reset_charge(&mut rng, &mut charge_particles);
         }
 
         let mut prog = dir.progress;
-        prog = prog * prog * (3. - 2. * prog);
+        prog = Easing::SmoothStep.ease(prog);
 
         let p = if dir.right { prog } else { 1. - prog };
         let angle = Vec2::X
@@ -108,9 +111,9 @@ pub fn cix_attack_sys(
         let current = time.elapsed_seconds_f64();
         let f = ((current - state.shoot) / CixLaser::CHARGE).clamp(0., 1.) as f32;
         for (particle, mut trns, mut sprite) in &mut charge_particles {
-            trns.translation = (particle.offset.lerp(Vec2::splat(0.), f * f * f) + ray_pos - pos).extend(10.);
+            trns.translation = (particle.offset.lerp(Vec2::splat(0.), Easing::CubicIn.ease(f)) + ray_pos - pos).extend(10.);
             sprite.color = CixLaser::COLOR.end().lerp(*CixLaser::COLOR.start(), f);
-            sprite.custom_size = Some(Vec2::splat(0.).lerp(Vec2::splat(particle.radius * 2.), 1. - (f - 1.) * (f - 1.)));
+            sprite.custom_size = Some(Vec2::splat(0.).lerp(Vec2::splat(particle.radius * 2.), Easing::QuadOut.ease(f)));
         }
 
         if current - state.shoot >= CixLaser::CHARGE {
@@ -145,13 +148,13 @@ pub fn cix_attack_sys(
                 Timed::new(CixLaser::LIFE),
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.laser_end),
+                        index: sprites.laser_end.index,
                         anchor: Anchor::Custom(Vec2::new(0.5, 0.)),
                         custom_size: Some(Vec2::new(CixLaser::WIDTH, CixLaser::WIDTH)),
                         color: *CixLaser::COLOR.start(),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.laser_end.atlas.clone_weak(),
                     transform: Transform::from_translation(ray_pos.extend(60.))
                         .with_rotation(Quat::from_axis_angle(Vec3::Z, angle)),
                     ..default()
@@ -159,27 +162,27 @@ pub fn cix_attack_sys(
             )).with_children(|builder| { builder.spawn(
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.laser),
+                        index: sprites.laser.index,
                         anchor: Anchor::Custom(Vec2::new(-0.5, 0.)),
                         custom_size: Some(Vec2::new(len + 1., CixLaser::WIDTH)),
                         color: *CixLaser::COLOR.start(),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.laser.atlas.clone_weak(),
                     transform: Transform::from_xyz(-0.5, 0., 0.),
                     ..default()
                 },
             ).with_children(|builder| { builder.spawn(
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.laser_end),
+                        index: sprites.laser_end.index,
                         anchor: Anchor::Custom(Vec2::new(0.5, 0.)),
                         custom_size: Some(Vec2::new(CixLaser::CAP_LENGTH, CixLaser::WIDTH)),
                         color: *CixLaser::COLOR.start(),
                         flip_y: true,
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.laser_end.atlas.clone_weak(),
                     transform: Transform::from_xyz(len + 0.5, 0., 0.)
                         .with_rotation(Quat::from_axis_angle(Vec3::Z, f32::PI)),
                     ..default()
@@ -187,7 +190,7 @@ pub fn cix_attack_sys(
             ); }); });
 
             state.shoot = current;
-            reset_charge(&mut charge_particles);
+            reset_charge(&mut rng, &mut charge_particles);
         }
     } else {
         for (mut arm, _) in &mut arms {
@@ -212,9 +215,9 @@ pub fn cix_attack_update_sys(
 
         let mut target = e;
         while let Ok((children, mut sprite)) = sprite.get_mut(target) {
-            sprite.color = CixLaser::COLOR.start().lerp(*CixLaser::COLOR.end(), 1. - (f - 1.) * (f - 1.));
+            sprite.color = CixLaser::COLOR.start().lerp(*CixLaser::COLOR.end(), Easing::QuadOut.ease(f));
             if let Some(custom_size) = sprite.custom_size.as_mut() {
-                custom_size.y = CixLaser::WIDTH * (1. - f * f);
+                custom_size.y = CixLaser::WIDTH * (1. - Easing::QuadIn.ease(f));
             }
 
             target = if let Some(children) = children {
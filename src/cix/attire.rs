@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 
 use crate::{
-    CixSprites, GameAtlas,
+    CixSprites, AtlasSprite,
     CixDirection,
+    Easing,
 };
 
 #[derive(Component, Copy, Clone, Eq, PartialEq)]
@@ -27,7 +28,7 @@ impl CixAttire {
     pub const ROTATE_SHRINK: f32 = 0.4;
 
     #[inline]
-    pub fn sprite(self, sprites: &CixSprites) -> &Handle<Image> {
+    pub fn sprite(self, sprites: &CixSprites) -> &AtlasSprite {
         use CixAttire::*;
         match self {
             RedCollar => &sprites.red_collar,
@@ -66,12 +67,11 @@ impl CixAttire {
 pub fn cix_direct_attire_sys(
     cix: Query<&CixDirection, Changed<CixDirection>>,
     mut attires: Query<(&CixAttire, &mut Transform, &mut TextureAtlasSprite)>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<CixSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<CixSprites>,
 ) {
     let Ok(&dir) = cix.get_single() else { return };
     let mut prog = dir.progress;
-    prog = prog * prog * (3. - 2. * prog);
+    prog = Easing::SmoothStep.ease(prog);
 
     let anchor_prog = (prog * 2. - 1.) * if dir.right { 1. } else { -1. };
     let size_prog = 1. - (0.5 - (prog - 0.5).abs()) * 2. * CixAttire::ROTATE_SHRINK;
@@ -81,7 +81,7 @@ pub fn cix_direct_attire_sys(
         let offset = attire.offset().x;
         trns.translation.x = offset * anchor_prog;
 
-        let sprite_size = atlas.rect(&atlases, attire.sprite(&sprites)).size();
+        let sprite_size = attire.sprite(&sprites).size();
         sprite.custom_size = Some(Vec2::new(
             sprite_size.x * size_prog,
             sprite_size.y,
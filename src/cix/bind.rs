@@ -0,0 +1,244 @@
+use bevy::prelude::*;
+use leafwing_input_manager::{
+    prelude::*,
+    axislike::AxisType,
+    user_input::InputKind,
+};
+
+use crate::{
+    CixAction,
+    Settings,
+    Lang,
+};
+
+/// One rebindable slot of [`BindingSettings::cix`](crate::BindingSettings::cix). Every slot holds one input per
+/// [`BindDevice`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum CixBinding {
+    Move(BindDirection),
+    Jump,
+    Attack,
+    Aim(BindDirection),
+    Retry,
+    Interact,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BindDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum BindDevice {
+    Keyboard,
+    Mouse,
+    Gamepad,
+}
+
+impl BindDevice {
+    pub const ALL: &'static [Self] = &[
+        Self::Keyboard,
+        Self::Mouse,
+        Self::Gamepad,
+    ];
+
+    /// How `input` of this device is shown to the player, e.g. `Mouse Left`.
+    pub fn describe(self, input: InputKind, lang: &Lang) -> String {
+        let input = input.to_string();
+        match self {
+            Self::Keyboard => input,
+            Self::Mouse => lang.format("bind.mouse", &[("input", &input)]),
+            Self::Gamepad => lang.format("bind.pad", &[("input", &input)]),
+        }
+    }
+
+    pub fn of(input: InputKind) -> Option<Self> {
+        match input {
+            InputKind::Keyboard(_) | InputKind::KeyLocation(_) | InputKind::Modifier(_) => Some(Self::Keyboard),
+            InputKind::Mouse(_) | InputKind::MouseWheel(_) | InputKind::MouseMotion(_) => Some(Self::Mouse),
+            InputKind::GamepadButton(_) => Some(Self::Gamepad),
+            InputKind::SingleAxis(axis) => Self::of_axis(axis.axis_type),
+            InputKind::DualAxis(axis) => Self::of_axis(axis.x.axis_type),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn of_axis(axis_type: AxisType) -> Option<Self> {
+        match axis_type {
+            AxisType::Gamepad(_) => Some(Self::Gamepad),
+            AxisType::MouseWheel(_) | AxisType::MouseMotion(_) => Some(Self::Mouse),
+        }
+    }
+}
+
+impl CixBinding {
+    pub const ALL: &'static [Self] = &[
+        Self::Move(BindDirection::Up),
+        Self::Move(BindDirection::Down),
+        Self::Move(BindDirection::Left),
+        Self::Move(BindDirection::Right),
+        Self::Jump,
+        Self::Attack,
+        Self::Aim(BindDirection::Up),
+        Self::Aim(BindDirection::Down),
+        Self::Aim(BindDirection::Left),
+        Self::Aim(BindDirection::Right),
+        Self::Retry,
+        Self::Interact,
+    ];
+
+    #[inline]
+    pub fn action(self) -> CixAction {
+        match self {
+            Self::Move(_) => CixAction::Move,
+            Self::Jump => CixAction::Jump,
+            Self::Attack => CixAction::Attack,
+            Self::Aim(_) => CixAction::Aim,
+            Self::Retry => CixAction::Retry,
+            Self::Interact => CixAction::Interact,
+        }
+    }
+
+    /// The [`Locale`](crate::Locale) key of its name.
+    #[inline]
+    pub fn key(self) -> &'static str {
+        use BindDirection::*;
+        match self {
+            Self::Move(Up) => "bind.move_up",
+            Self::Move(Down) => "bind.move_down",
+            Self::Move(Left) => "bind.move_left",
+            Self::Move(Right) => "bind.move_right",
+            Self::Jump => "bind.jump",
+            Self::Attack => "bind.attack",
+            Self::Aim(Up) => "bind.aim_up",
+            Self::Aim(Down) => "bind.aim_down",
+            Self::Aim(Left) => "bind.aim_left",
+            Self::Aim(Right) => "bind.aim_right",
+            Self::Retry => "bind.retry",
+            Self::Interact => "bind.interact",
+        }
+    }
+
+    /// Every input bound to this slot as shown to the player, in [`BindDevice::ALL`]'s order.
+    pub fn describe(self, input_map: &InputMap<CixAction>, lang: &Lang) -> Vec<String> {
+        BindDevice::ALL.iter()
+            .filter_map(|&device| self.get(input_map, device).map(|input| device.describe(input, lang)))
+            .collect()
+    }
+
+    /// The input bound to this slot for `device`, if any.
+    pub fn get(self, input_map: &InputMap<CixAction>, device: BindDevice) -> Option<InputKind> {
+        input_map.get(self.action()).iter().find_map(|input| match (self, input) {
+            (Self::Jump | Self::Attack | Self::Retry | Self::Interact, &UserInput::Single(kind)) => Some(kind),
+            (Self::Move(dir) | Self::Aim(dir), UserInput::VirtualDPad(dpad)) => Some(*dir.of(dpad)),
+            _ => None,
+        }.filter(|&kind| BindDevice::of(kind) == Some(device)))
+    }
+
+    /// Binds `kind` to this slot, replacing whatever was bound for the same [`BindDevice`]. Directions are bound
+    /// through a [`VirtualDPad`]; if the device doesn't have one for this action yet, one is made from its defaults.
+    pub fn set(self, input_map: &mut InputMap<CixAction>, kind: InputKind) {
+        let Some(device) = BindDevice::of(kind) else { return };
+        let action = self.action();
+        let inputs = input_map.get(action).iter().cloned().collect::<Vec<_>>();
+
+        match self {
+            Self::Jump | Self::Attack | Self::Retry | Self::Interact => {
+                for input in inputs {
+                    if let UserInput::Single(old) = input && BindDevice::of(old) == Some(device) {
+                        input_map.remove(action, input);
+                    }
+                }
+
+                input_map.insert(kind, action);
+            },
+            Self::Move(dir) | Self::Aim(dir) => {
+                let old = inputs.into_iter().find_map(|input| match input {
+                    UserInput::VirtualDPad(dpad) if BindDevice::of(dpad.up) == Some(device) => Some(dpad),
+                    _ => None,
+                });
+
+                let mut dpad = match old {
+                    Some(dpad) => {
+                        input_map.remove(action, dpad.clone());
+                        dpad
+                    },
+                    None => match device {
+                        BindDevice::Gamepad => VirtualDPad::dpad(),
+                        _ => VirtualDPad::arrow_keys(),
+                    },
+                };
+
+                *dir.of_mut(&mut dpad) = kind;
+                input_map.insert(dpad, action);
+            },
+        }
+    }
+}
+
+impl BindDirection {
+    #[inline]
+    pub fn of(self, dpad: &VirtualDPad) -> &InputKind {
+        match self {
+            Self::Up => &dpad.up,
+            Self::Down => &dpad.down,
+            Self::Left => &dpad.left,
+            Self::Right => &dpad.right,
+        }
+    }
+
+    #[inline]
+    pub fn of_mut(self, dpad: &mut VirtualDPad) -> &mut InputKind {
+        match self {
+            Self::Up => &mut dpad.up,
+            Self::Down => &mut dpad.down,
+            Self::Left => &mut dpad.left,
+            Self::Right => &mut dpad.right,
+        }
+    }
+}
+
+/// The [`CixBinding`] waiting for the next key or button pressed, if any. Escape cancels.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct CixRebind(pub Option<CixBinding>);
+
+/// Captured inputs are consumed, so they don't also trigger whatever they were bound to before.
+pub fn cix_rebind_sys(
+    mut rebind: ResMut<CixRebind>,
+    mut settings: ResMut<Settings>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut mouse_buttons: ResMut<Input<MouseButton>>,
+    mut gamepad_buttons: ResMut<Input<GamepadButton>>,
+) {
+    let Some(binding) = **rebind else { return };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        keys.reset(KeyCode::Escape);
+        **rebind = None;
+        return;
+    }
+
+    let key = keys.get_just_pressed().next().copied();
+    let mouse_button = mouse_buttons.get_just_pressed().next().copied();
+    let gamepad_button = gamepad_buttons.get_just_pressed().next().copied();
+
+    let kind = if let Some(key) = key {
+        keys.reset(key);
+        InputKind::Keyboard(key)
+    } else if let Some(button) = mouse_button {
+        mouse_buttons.reset(button);
+        InputKind::Mouse(button)
+    } else if let Some(button) = gamepad_button {
+        gamepad_buttons.reset(button);
+        InputKind::GamepadButton(button.button_type)
+    } else {
+        return;
+    };
+
+    binding.set(&mut settings.bindings.cix, kind);
+    **rebind = None;
+}
@@ -5,42 +5,51 @@ use rand::{
 };
 
 use crate::{
-    ext::*,
-    GenericSprites, GameAtlas,
+    GenericSprites,
     Cix, CixStates, CixSpawn, CixSpawnPos,
+    CixAction, CixActState,
     CameraPos,
-    Timed,
+    Timed, Tween, Keyframes, Easing,
     DeathEvent,
+    GameRng,
 };
 
 #[derive(Component, Copy, Clone)]
 pub enum CixDeathParticle {
     Blast,
-    Large {
-        init: Vec2,
-        offset: Vec2,
-        radius: f32,
-    },
-    Small {
-        init: Vec2,
-        offset: Vec2,
-        radius: f32,
-    },
+    Large,
+}
+
+impl CixDeathParticle {
+    /// The color it fades through over its lifetime.
+    pub fn colors(self) -> (Color, Color) {
+        match self {
+            Self::Blast | Self::Large => (Color::rgba(0.4, 1.8, 3., 0.36), Color::rgba(0., 0.4, 1., 0.)),
+        }
+    }
+
+    /// Flies out from `init` by `offset` while shrinking away.
+    pub fn scatter(self, init: Vec2, offset: Vec2, radius: f32) -> Tween {
+        let (color_from, color_to) = self.colors();
+        Tween::new()
+            .translation(Keyframes::between(init, init + offset, Easing::QuadOut))
+            .color(Keyframes::between(color_from, color_to, Easing::QuadIn))
+            .size(Keyframes::between(Vec2::splat(radius * 2.), Vec2::ZERO, Easing::QuadIn))
+    }
 }
 
 pub fn cix_check_alive_sys(
     mut commands: Commands,
     mut state: ResMut<NextState<CixStates>>,
-    mut events: EventReader<DeathEvent>,
+    mut events: EventReader<DeathEvent>, mut rng: ResMut<GameRng>,
     cix: Query<&GlobalTransform, With<Cix>>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<GenericSprites>,
 ) {
     for &DeathEvent(e) in &mut events {
         if let Ok(&global_trns) = cix.get(e) {
             state.set(CixStates::Dead);
 
-            let mut rng = thread_rng();
+            let rng = &mut **rng;
             let angle = Uniform::from(0f32..(360f32).to_radians());
             let dist_large = Uniform::from(80f32..=160f32);
             let dist_small = Uniform::from(160f32..=320f32);
@@ -50,36 +59,41 @@ pub fn cix_check_alive_sys(
             let time_small = Uniform::from(0.5f64..=1.3f64);
             let init = global_trns.translation().truncate();
 
+            let (color_from, color_to) = CixDeathParticle::Blast.colors();
             commands.spawn((
                 CixDeathParticle::Blast,
                 Timed::new(0.2),
+                Tween::new()
+                    .color(Keyframes::between(color_from, color_to, Easing::CubicOut))
+                    .size(Keyframes::between(Vec2::splat(64.), Vec2::splat(320.), Easing::QuadOut)),
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.circle),
-                        color: Color::rgba(0.4, 1.8, 3., 0.36),
+                        index: sprites.circle.index,
+                        color: color_from,
                         custom_size: Some(Vec2::splat(64.)),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.circle.atlas.clone_weak(),
                     transform: global_trns.into(),
                     ..default()
                 },
             ));
 
             for _ in 0..8 {
-                let offset = Vec2::from_angle(angle.sample(&mut rng)) * dist_large.sample(&mut rng);
-                let radius = radius_large.sample(&mut rng);
+                let offset = Vec2::from_angle(angle.sample(rng)) * dist_large.sample(rng);
+                let radius = radius_large.sample(rng);
                 commands.spawn((
-                    CixDeathParticle::Large { init, offset, radius, },
-                    Timed::new(time_large.sample(&mut rng)),
+                    CixDeathParticle::Large,
+                    Timed::new(time_large.sample(rng)),
+                    CixDeathParticle::Large.scatter(init, offset, radius),
                     SpriteSheetBundle {
                         sprite: TextureAtlasSprite {
-                            index: atlas.index(&atlases, &sprites.circle),
-                            color: Color::rgba(0.4, 1.8, 3., 0.36),
+                            index: sprites.circle.index,
+                            color: CixDeathParticle::Large.colors().0,
                             custom_size: Some(Vec2::splat(radius * 2.)),
                             ..default()
                         },
-                        texture_atlas: atlas.clone_weak(),
+                        texture_atlas: sprites.circle.atlas.clone_weak(),
                         transform: global_trns.into(),
                         ..default()
                     },
@@ -87,19 +101,20 @@ pub fn cix_check_alive_sys(
             }
 
             for _ in 0..32 {
-                let offset = Vec2::from_angle(angle.sample(&mut rng)) * dist_small.sample(&mut rng);
-                let radius = radius_small.sample(&mut rng);
+                let offset = Vec2::from_angle(angle.sample(rng)) * dist_small.sample(rng);
+                let radius = radius_small.sample(rng);
                 commands.spawn((
-                    CixDeathParticle::Large { init, offset, radius, },
-                    Timed::new(time_small.sample(&mut rng)),
+                    CixDeathParticle::Large,
+                    Timed::new(time_small.sample(rng)),
+                    CixDeathParticle::Large.scatter(init, offset, radius),
                     SpriteSheetBundle {
                         sprite: TextureAtlasSprite {
-                            index: atlas.index(&atlases, &sprites.circle),
-                            color: Color::rgba(0.1, 0.6, 2., 0.24),
+                            index: sprites.circle.index,
+                            color: CixDeathParticle::Large.colors().0,
                             custom_size: Some(Vec2::splat(radius * 2.)),
                             ..default()
                         },
-                        texture_atlas: atlas.clone_weak(),
+                        texture_atlas: sprites.circle.atlas.clone_weak(),
                         transform: global_trns.into(),
                         ..default()
                     },
@@ -109,42 +124,26 @@ pub fn cix_check_alive_sys(
     }
 }
 
-pub fn cix_update_death_sys(mut particles: Query<(&CixDeathParticle, &Timed, &mut Transform, &mut TextureAtlasSprite)>) {
-    for (&particle, &timed, mut trns, mut sprite) in &mut particles {
-        let f = timed.fin();
-        let (color_from, color_to) = match particle {
-            CixDeathParticle::Blast | CixDeathParticle::Large { .. } => (Color::rgba(0.4, 1.8, 3., 0.36), Color::rgba(0., 0.4, 1., 0.)),
-            CixDeathParticle::Small { .. } => (Color::rgba(0.1, 0.6, 2., 0.24), Color::rgba(0., 0.1, 0.5, 0.)),
-        };
+/// Counts down [`CixSpawn::RESPAWN_TIME`] while Cix is dead, unless [`CixAction::Retry`] cuts it short. Cix respawns
+/// at [`CixSpawnPos`], which the last activated [`Checkpoint`](crate::Checkpoint) moves.
+#[derive(Component)]
+pub struct CixRespawn;
 
-        match particle {
-            CixDeathParticle::Blast => {
-                sprite.color = color_from.lerp(color_to, (f - 1.) * (f - 1.) * (f - 1.) + 1.);
-                sprite.custom_size = Some(Vec2::splat(64.).lerp(Vec2::splat(320.), 1. - (f - 1.) * (f - 1.)));
-            },
-            CixDeathParticle::Large { init, offset, radius, } | CixDeathParticle::Small { init, offset, radius, } => {
-                trns.translation = (init + offset * (1. - (f - 1.) * (f - 1.))).extend(trns.translation.z);
-                sprite.color = color_from.lerp(color_to, f * f);
-                sprite.custom_size = Some(Vec2::splat(radius * 2. * (1. - f * f)));
-            },
-        }
-    }
+pub fn cix_init_respawn_sys(mut commands: Commands) {
+    commands.spawn((
+        CixRespawn,
+        Timed::new(CixSpawn::RESPAWN_TIME),
+    ));
 }
 
 pub fn cix_respawn_sys(
-    time: Res<Time>,
     mut state: ResMut<NextState<CixStates>>,
     mut camera_pos: ResMut<CameraPos>, cix_pos: Res<CixSpawnPos>,
-    mut start: Local<Option<f64>>,
+    input: Res<CixActState>,
+    respawn: Query<&Timed, With<CixRespawn>>,
 ) {
-    let current = time.elapsed_seconds_f64();
-    if start.is_none() {
-        *start = Some(current);
-    }
-
-    if current - start.unwrap() >= CixSpawn::RESPAWN_TIME {
-        *start = None;
-
+    let Ok(&timed) = respawn.get_single() else { return };
+    if timed.ended() || input.just_pressed(CixAction::Retry) {
         **camera_pos = **cix_pos;
         state.set(CixStates::Spawning);
     }
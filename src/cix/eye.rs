@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::CixDirection;
+use crate::{
+    CixDirection, CixArm, CixAim,
+    Easing,
+};
 
 #[derive(Component)]
 pub struct CixEye;
@@ -14,33 +17,23 @@ impl CixEye {
 }
 
 pub fn cix_update_eye_sys(
-    window: Query<&Window>,
-    camera: Query<(&Camera, &GlobalTransform)>,
-    cix: Query<&CixDirection>,
+    cix: Query<(&CixDirection, &CixAim, &GlobalTransform)>,
     mut eye: Query<(&mut Transform, &GlobalTransform), With<CixEye>>,
-    mut cursor_pos: Local<Vec2>,
 ) {
-    let Ok(&dir) = cix.get_single() else { return };
-    let (camera, &camera_trns) = camera.single();
+    let Ok((&dir, &aim, &cix_trns)) = cix.get_single() else { return };
+    let Some(at) = aim.at else { return };
     let Ok((mut trns, &global_trns)) = eye.get_single_mut() else { return };
 
-    if let Some(pos) = window
-        .get_single().ok()
-        .and_then(|window| window.cursor_position())
-        .and_then(|pos| camera.viewport_to_world_2d(&camera_trns, pos))
-    {
-        *cursor_pos = pos;
-    }
-
     let eye_trns = global_trns.translation();
+    let target = cix_trns.translation().truncate() + CixArm::TARGET_POINT + at;
 
-    let vec = (*cursor_pos - Vec2::new(eye_trns.x, eye_trns.y)).clamp_length_max(CixEye::FOCUS);
+    let vec = (target - Vec2::new(eye_trns.x, eye_trns.y)).clamp_length_max(CixEye::FOCUS);
     let mut len = vec.length() / CixEye::FOCUS;
     len = 1. - (len - 1.) * (len - 1.);
     len *= CixEye::DEVIATE;
 
     let mut prog = dir.progress;
-    prog = prog * prog * (3. - 2. * prog);
+    prog = Easing::SmoothStep.ease(prog);
     prog = (prog * 2. - 1.) * if dir.right { 1. } else { -1. };
 
     let mut deviate = vec * ((len * len) / vec.length_squared()).sqrt();
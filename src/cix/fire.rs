@@ -5,8 +5,9 @@ use rand::prelude::*;
 use crate::{
     ext::*,
     PIXELS_PER_METER,
-    GenericSprites, GameAtlas,
-    Cix, Timed,
+    GenericSprites,
+    Cix, Timed, Easing,
+    GameRng,
 };
 
 use std::ops::RangeInclusive as RangeIncl;
@@ -28,17 +29,16 @@ impl CixFire {
 }
 
 pub fn cix_spawn_fire_sys(
-    mut commands: Commands, time: Res<Time>,
+    mut commands: Commands, mut rng: ResMut<GameRng>,
     cix: Query<(&GlobalTransform, &TextureAtlasSprite, &Velocity), With<Cix>>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<GenericSprites>,
 ) {
-    let mut rng = thread_rng();
+    let rng = &mut **rng;
 
     let Ok((&global_trns, sprite, &vel)) = cix.get_single() else { return };
     let trns = global_trns.translation();
 
-    if rng.gen_range(0f32..=1f32) <= CixFire::CHANCE * time.delta_seconds() * 60. {
+    if rng.gen_range(0f32..=1f32) <= CixFire::CHANCE {
         let (sin, cos) = rng.gen_range(0f32..(180f32.to_radians())).sin_cos();
         let radius = rng.gen_range(CixFire::RADIUS);
 
@@ -53,11 +53,11 @@ pub fn cix_spawn_fire_sys(
             SpriteSheetBundle {
                 sprite: TextureAtlasSprite {
                     color: CixFire::COLOR.start().with_a(CixFire::COLOR.start().a() * alpha),
-                    index: atlas.index(&atlases, &sprites.circle),
+                    index: sprites.circle.index,
                     custom_size: Some(Vec2::splat(radius * 2.)),
                     ..default()
                 },
-                texture_atlas: atlas.clone_weak(),
+                texture_atlas: sprites.circle.atlas.clone_weak(),
                 transform: Transform::from_xyz(trns.x + cos * rad, trns.y + sin * rad, trns.z + 1.),
                 ..default()
             },
@@ -65,21 +65,17 @@ pub fn cix_spawn_fire_sys(
     }
 }
 
-pub fn cix_update_fire_sys(
-    time: Res<Time>,
-    mut fires: Query<(&CixFire, &Timed, &mut Transform, &mut TextureAtlasSprite)>,
-) {
-    let delta = time.delta_seconds() * 60.;
+pub fn cix_update_fire_sys(mut fires: Query<(&CixFire, &Timed, &mut Transform, &mut TextureAtlasSprite)>) {
     for (fire, timed, mut trns, mut sprite) in &mut fires {
         let f = timed.fin();
-        let col = 1. - (f - 1.) * (f - 1.);
-        let rad = 1. - f * f;
+        let col = Easing::QuadOut.ease(f);
+        let rad = 1. - Easing::QuadIn.ease(f);
 
         let color = &mut sprite.color;
         *color = CixFire::COLOR.start().lerp(*CixFire::COLOR.end(), col);
         color.set_a(color.a() * fire.alpha);
 
         sprite.custom_size = Some(Vec2::splat(fire.radius * 2. * rad));
-        trns.translation += (fire.velocity * (delta * (1. - f))).extend(0.);
+        trns.translation += (fire.velocity * (1. - f)).extend(0.);
     }
 }
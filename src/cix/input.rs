@@ -1,25 +1,44 @@
 use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 use leafwing_input_manager::prelude::*;
+use serde::{
+    Serialize, Deserialize,
+};
 
 use crate::{
     PIXELS_PER_METER,
     Cix, CixGrounded, CixLastGrounded, CixHovered, CixDirection,
-    CixArm,
+    CixArm, CixEye,
     CixAttack,
+    GameTime, Easing,
 };
 
 pub const CIX_MOVE_VEL: f32 = 3.;
 
-#[derive(Actionlike, PartialEq, Eq, Clone, Copy, Hash, Debug)]
+#[derive(Actionlike, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug, Serialize, Deserialize)]
 pub enum CixAction {
     Move,
     Jump,
     Attack,
+    /// Aims with a stick or a [`VirtualDPad`] instead of the cursor.
+    Aim,
+    /// Skips [`CixSpawn::RESPAWN_TIME`](crate::CixSpawn::RESPAWN_TIME) after dying.
+    Retry,
+    /// Reads [`Sign`](crate::Sign)s.
+    Interact,
 }
 
 pub type CixActState = ActionState<CixAction>;
 
+/// Where Cix looks and shoots at, relative to [`CixArm::TARGET_POINT`]. Once [`CixAction::Aim`] is used, the cursor
+/// only takes over again after it moves, so a mouse resting over the window doesn't steal the aim back.
+#[derive(Component, Copy, Clone, Default)]
+pub struct CixAim {
+    pub at: Option<Vec2>,
+    pub cursor: Option<Vec2>,
+    pub axis: bool,
+}
+
 #[derive(Component, Copy, Clone, Default)]
 pub struct CixJumpState {
     pub jump_time: Option<f64>,
@@ -54,19 +73,11 @@ pub fn cix_move_sys(mut cix: Query<
     }
 }
 
-pub fn cix_flip_direction_sys(
-    window: Query<&Window>, camera: Query<(&Camera, &GlobalTransform)>,
-    mut cix: Query<(&CixActState, &mut CixDirection, &GlobalTransform)>,
-) {
-    let Ok(window) = window.get_single() else { return };
-    let Ok((input, mut dir, &global_trns)) = cix.get_single_mut() else { return };
-    let (camera, &camera_trns) = camera.single();
+pub fn cix_flip_direction_sys(mut cix: Query<(&CixActState, &CixAttack, &mut CixDirection)>) {
+    let Ok((input, attack, mut dir)) = cix.get_single_mut() else { return };
 
-    let Some(right) = (if input.pressed(CixAction::Attack) && let Some(pos) = window
-        .cursor_position()
-        .and_then(|pos| camera.viewport_to_world_2d(&camera_trns, pos))
-    {
-        Some(pos.x > global_trns.translation().x)
+    let Some(right) = (if input.pressed(CixAction::Attack) {
+        Some(attack.at.x + CixArm::TARGET_POINT.x > 0.)
     } else if let Some(axis) = input.axis_pair(CixAction::Move) {
         let move_x = axis.x();
         if move_x != 0. {
@@ -85,7 +96,7 @@ pub fn cix_flip_direction_sys(
 }
 
 pub fn cix_jump_sys(
-    time: Res<Time>,
+    time: Res<GameTime>,
     mut cix: Query<(
         &mut CixJumpState, &CixActState,
         &GlobalTransform, &CixGrounded, &CixLastGrounded,
@@ -97,7 +108,7 @@ pub fn cix_jump_sys(
         let current = time.elapsed_seconds_f64();
         if let Some(jump_time) = state.jump_time {
             let f = 1. - (current - jump_time).min(CixJumpState::DURATION) / CixJumpState::DURATION;
-            let f = (f * f * f) as f32;
+            let f = Easing::CubicIn.ease(f as f32);
 
             let trns = global_trns.translation().truncate();
             *force += ExternalForce::at_point(Vec2::new(0., CixJumpState::FORCE * PIXELS_PER_METER * f), trns, trns);
@@ -134,23 +145,45 @@ pub fn cix_jump_sys(
     }
 }
 
-pub fn cix_attack_input_sys(
-    time: Res<Time>,
+pub fn cix_aim_sys(
     window: Query<&Window>, camera: Query<(&Camera, &GlobalTransform)>,
-    mut cix: Query<(&CixActState, &GlobalTransform, &mut CixAttack)>,
+    mut cix: Query<(&CixActState, &CixAttack, &GlobalTransform, &mut CixAim, Option<&InputMap<CixAction>>)>,
 ) {
-    let Ok(window) = window.get_single() else { return };
-    let (camera, &camera_trns) = camera.single();
+    let Ok((input, &attack, &global_trns, mut aim, input_map)) = cix.get_single_mut() else { return };
+    if input_map.is_none() {
+        aim.at = Some(attack.at);
+        return;
+    }
+
+    let cursor = window.get_single().ok().and_then(|window| window.cursor_position());
+    let moved = cursor.is_some() && cursor != aim.cursor;
+    aim.cursor = cursor;
+
+    if let Some(axis) = input.axis_pair(CixAction::Aim) && axis.xy() != Vec2::ZERO {
+        aim.at = Some(axis.xy().clamp_length_max(1.) * CixEye::FOCUS);
+        aim.axis = true;
+    } else if
+        (moved || !aim.axis) &&
+        let Some(cursor) = cursor &&
+        let Ok((camera, &camera_trns)) = camera.get_single() &&
+        let Some(pos) = camera.viewport_to_world_2d(&camera_trns, cursor)
+    {
+        aim.at = Some(pos - (global_trns.translation().truncate() + CixArm::TARGET_POINT));
+        aim.axis = false;
+    }
+}
 
-    let Ok((input, &global_trns, mut attack)) = cix.get_single_mut() else { return };
+pub fn cix_attack_input_sys(
+    time: Res<GameTime>,
+    mut cix: Query<(&CixActState, &CixAim, &mut CixAttack, Option<&InputMap<CixAction>>)>,
+) {
+    let Ok((input, &aim, mut attack, input_map)) = cix.get_single_mut() else { return };
     if input.just_pressed(CixAction::Attack) {
         attack.init = time.elapsed_seconds_f64();
     }
 
-    if input.pressed(CixAction::Attack) && let Some(pos) = window
-        .cursor_position()
-        .and_then(|pos| camera.viewport_to_world_2d(&camera_trns, pos))
-    {
-        attack.at = pos - (global_trns.translation().truncate() + CixArm::TARGET_POINT);
+    // Without an input map, Cix is driven by a script or a replay that supplies the aim itself.
+    if input_map.is_some() && input.pressed(CixAction::Attack) && let Some(at) = aim.at {
+        attack.at = at;
     }
 }
@@ -1,10 +1,22 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    input::InputSystem,
+};
 use bevy_rapier2d::prelude::*;
+use leafwing_input_manager::{
+    prelude::*,
+    systems::{
+        tick_action_state, update_action_state, release_on_input_map_removed,
+    },
+};
 
 use crate::{
     ext::*,
     GROUP_GROUND,
+    GameStates, FixedSet,
     CameraPos,
+    GameTime,
+    Settings,
 };
 
 use std::ops::RangeInclusive as RangeIncl;
@@ -12,6 +24,7 @@ use std::ops::RangeInclusive as RangeIncl;
 mod arm;
 mod attack;
 mod attire;
+mod bind;
 mod death;
 mod input;
 mod particle;
@@ -23,6 +36,7 @@ mod spawner;
 pub use arm::*;
 pub use attack::*;
 pub use attire::*;
+pub use bind::*;
 pub use death::*;
 pub use input::*;
 pub use particle::*;
@@ -40,6 +54,99 @@ pub enum CixStates {
     Dead,
 }
 
+pub struct CixPlugin;
+impl Plugin for CixPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_state::<CixStates>()
+            .insert_resource(CixSpawnPos(Vec2::splat(0.)))
+            // Only read for the bindings; headless apps never load a settings file.
+            .init_resource::<Settings>();
+
+        // Cix's entity is gone while dead, so retrying is read from these instead of its own action state.
+        let input_map = app.world.resource::<Settings>().bindings.cix.clone();
+        app
+            .insert_resource(input_map)
+            .init_resource::<CixActState>()
+
+            // `InputManagerPlugin` would tick per frame; Cix's actions are ticked per fixed step instead, so that
+            // `just_pressed` holds for exactly one step.
+            .register_type::<CixActState>()
+            .init_resource::<ClashStrategy>()
+            .add_systems((
+                tick_action_state::<CixAction>,
+                update_action_state::<CixAction>,
+            )
+                .chain()
+                .in_base_set(FixedSet::First)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(release_on_input_map_removed::<CixAction>.in_base_set(CoreSet::PostUpdate))
+
+            .init_resource::<CixRebind>()
+            .add_system(cix_rebind_sys
+                .in_base_set(CoreSet::PreUpdate)
+                .after(InputSystem)
+            )
+
+            .add_systems((cix_pre_update_sys, cix_follow_camera_sys)
+                .in_base_set(FixedSet::PreUpdate)
+                .distributive_run_if(in_state(CixStates::Alive))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+
+            .add_system(cix_init_spawn_sys.in_schedule(OnEnter(CixStates::Spawning)))
+            .add_system(cix_update_spawn_sys
+                .in_base_set(FixedSet::Update)
+                .run_if(in_state(CixStates::Spawning))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems((
+                cix_update_fire_sys,
+                cix_update_particle_sys,
+                cix_attack_update_sys,
+            )
+                .in_base_set(FixedSet::Update)
+                .distributive_run_if(in_state(GameStates::Gameplay))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems((
+                cix_check_alive_sys,
+                cix_update_sys,
+                cix_update_head_sys,
+                cix_spawn_particle_sys.after(cix_update_head_sys),
+                cix_flip_direction_sys.after(cix_attack_input_sys),
+                cix_update_direction_sys.after(cix_flip_direction_sys),
+                cix_direct_attire_sys.after(cix_update_direction_sys),
+                cix_update_arm_sys,
+                cix_spawn_fire_sys,
+                cix_aim_sys,
+                cix_update_eye_sys.after(cix_aim_sys),
+            )
+                .in_base_set(FixedSet::Update)
+                .distributive_run_if(in_state(CixStates::Alive))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems((
+                // These all add to Cix's external force, so they have to run in a fixed order.
+                cix_move_sys.after(cix_update_sys),
+                cix_jump_sys.after(cix_move_sys),
+                cix_attack_input_sys.after(cix_aim_sys),
+                cix_attack_sys.after(cix_attack_input_sys),
+            )
+                .in_base_set(FixedSet::Update)
+                .distributive_run_if(in_state(CixStates::Alive))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(cix_init_respawn_sys.in_schedule(OnEnter(CixStates::Dead)))
+            .add_system(cix_respawn_sys
+                .in_base_set(FixedSet::Update)
+                .run_if(in_state(CixStates::Dead))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            );
+    }
+}
+
 #[derive(Component)]
 pub struct Cix;
 impl Cix {
@@ -82,7 +189,7 @@ pub fn cix_pre_update_sys(
 }
 
 pub fn cix_update_sys(
-    time: Res<Time>,
+    time: Res<GameTime>,
     context: Res<RapierContext>,
     mut cix: Query<(
         &mut CixGrounded, &mut CixLastGrounded, &mut CixHovered,
@@ -124,7 +231,7 @@ pub fn cix_update_sys(
 }
 
 pub fn cix_update_head_sys(
-    time: Res<Time>,
+    time: Res<GameTime>,
     mut cix: Query<&mut TextureAtlasSprite, With<Cix>>,
 ) {
     let absin = (time.elapsed_seconds() * Cix::WAVE_SCALE).sin() / 2. + 0.5;
@@ -134,21 +241,17 @@ pub fn cix_update_head_sys(
     sprite.custom_size = Some(Vec2::splat((Cix::RADIUS.start() + absin * (Cix::RADIUS.end() - Cix::RADIUS.start())) * 2.));
 }
 
-pub fn cix_update_direction_sys(
-    time: Res<Time>,
-    mut cix: Query<&mut CixDirection>,
-) {
+pub fn cix_update_direction_sys(mut cix: Query<&mut CixDirection>) {
     let Ok(mut dir) = cix.get_single_mut() else { return };
     if dir.progress < 1. {
-        dir.progress = (dir.progress + time.delta_seconds() * CixDirection::TURN_SPEED).min(1.);
+        dir.progress = (dir.progress + GameTime::STEP as f32 * CixDirection::TURN_SPEED).min(1.);
     }
 }
 
 pub fn cix_follow_camera_sys(
-    time: Res<Time>,
     mut pos: ResMut<CameraPos>,
     cix: Query<&GlobalTransform, With<Cix>>,
 ) {
     let Ok(&global_trns) = cix.get_single() else { return };
-    **pos = pos.lerp(global_trns.translation().truncate(), time.delta_seconds() * 60. * 0.12);
+    **pos = pos.lerp(global_trns.translation().truncate(), 0.12);
 }
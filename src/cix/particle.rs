@@ -7,8 +7,9 @@ use rand::{
 
 use crate::{
     ext::*,
-    GenericSprites, GameAtlas,
-    Cix, Timed,
+    GenericSprites,
+    Cix, Timed, Easing,
+    GameRng,
 };
 
 use std::ops::RangeInclusive as RangeIncl;
@@ -28,12 +29,11 @@ impl CixParticle {
 }
 
 pub fn cix_spawn_particle_sys(
-    mut commands: Commands, time: Res<Time>,
+    mut commands: Commands, mut rng: ResMut<GameRng>,
     cix: Query<(Entity, &TextureAtlasSprite), With<Cix>>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<GenericSprites>,
 ) {
-    let mut rng = thread_rng();
+    let rng = &mut **rng;
     let angle_rng = Uniform::from(0f32..(360f32.to_radians()));
     let dst_rng = Uniform::from(0.3f32..=1f32);
     let alpha_rng = Uniform::from(CixParticle::ALPHA);
@@ -42,29 +42,27 @@ pub fn cix_spawn_particle_sys(
 
     let Ok((cix, sprite)) = cix.get_single() else { return };
     commands.entity(cix).with_children(|builder| {
-        for _ in 0..((
-            rng.gen_range(CixParticle::COUNT) as f32 * time.delta_seconds() * 60.
-        ) as u32).min(CixParticle::COUNT.end() * 2) {
-            let (sin, cos) = angle_rng.sample(&mut rng).sin_cos();
-            let radius = radius_rng.sample(&mut rng);
+        for _ in 0..rng.gen_range(CixParticle::COUNT) {
+            let (sin, cos) = angle_rng.sample(rng).sin_cos();
+            let radius = radius_rng.sample(rng);
 
-            let mut dst = 1. - dst_rng.sample(&mut rng);
+            let mut dst = 1. - dst_rng.sample(rng);
             dst = 1. - dst * dst;
 
-            let alpha = alpha_rng.sample(&mut rng);
+            let alpha = alpha_rng.sample(rng);
             let r = (sprite.custom_size.unwrap().x / 2. - radius / 2.) * dst;
 
             builder.spawn((
                 CixParticle { alpha, radius, },
-                Timed::new(lifetime_rng.sample(&mut rng)),
+                Timed::new(lifetime_rng.sample(rng)),
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
                         color: CixParticle::COLOR.start().with_a(CixParticle::COLOR.start().a() * alpha),
-                        index: atlas.index(&atlases, &sprites.circle),
+                        index: sprites.circle.index,
                         custom_size: Some(Vec2::splat(radius * 2.)),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.circle.atlas.clone_weak(),
                     transform: Transform::from_xyz(cos * r, sin * r, 1.),
                     ..default()
                 },
@@ -75,9 +73,8 @@ pub fn cix_spawn_particle_sys(
 
 pub fn cix_update_particle_sys(mut particles: Query<(&CixParticle, &Timed, &mut TextureAtlasSprite)>) {
     for (particle, timed, mut sprite) in &mut particles {
-        let f = timed.fin();
-        let col = f * f;
-        let rad = 1. - f * f;
+        let col = timed.ease(Easing::QuadIn);
+        let rad = 1. - col;
 
         let color = &mut sprite.color;
         *color = CixParticle::COLOR.start().lerp(*CixParticle::COLOR.end(), col);
@@ -6,9 +6,11 @@ use rand::{
 
 use crate::{
     ext::*,
-    GenericSprites, CixSprites, GameAtlas,
+    GenericSprites, CixSprites,
     CixStates,
-    Timed,
+    Timed, Easing,
+    GameRng,
+    Settings,
 };
 
 #[derive(Component)]
@@ -28,11 +30,10 @@ pub struct CixSpawnParticle {
 pub struct CixSpawnPos(pub Vec2);
 
 pub fn cix_init_spawn_sys(
-    mut commands: Commands, pos: Res<CixSpawnPos>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    mut commands: Commands, pos: Res<CixSpawnPos>, mut rng: ResMut<GameRng>,
+    sprites: Res<GenericSprites>,
 ) {
-    let mut rng = thread_rng();
+    let rng = &mut **rng;
     let angle = Uniform::from(0f32..(360f32).to_radians());
     let dist = Uniform::from(160f32..=320f32);
     let radius = Uniform::from(2f32..=4f32);
@@ -45,20 +46,20 @@ pub fn cix_init_spawn_sys(
         SpatialBundle::from(Transform::from_translation(pos.extend(50.))),
     )).with_children(|builder| {
         for _ in 0..48 {
-            let offset = Vec2::from_angle(angle.sample(&mut rng)) * dist.sample(&mut rng);
+            let offset = Vec2::from_angle(angle.sample(rng)) * dist.sample(rng);
             builder.spawn((
                 CixSpawnParticle {
                     offset,
-                    radius: radius.sample(&mut rng),
+                    radius: radius.sample(rng),
                 },
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.circle),
+                        index: sprites.circle.index,
                         color: Color::NONE,
                         custom_size: Some(Vec2::splat(0.)),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.circle.atlas.clone_weak(),
                     transform: Transform::from_translation(offset.extend(0.)),
                     ..default()
                 },
@@ -71,20 +72,20 @@ pub fn cix_update_spawn_sys(
     mut commands: Commands, mut state: ResMut<NextState<CixStates>>,
     spawn: Query<(&Timed, &GlobalTransform), With<CixSpawn>>,
     mut particles: Query<(&CixSpawnParticle, &mut Transform, &mut TextureAtlasSprite)>,
-    atlases: Res<Assets<TextureAtlas>>,
-    generic_sprites: Res<GenericSprites>, cix_sprites: Res<CixSprites>, atlas: Res<GameAtlas>,
+    generic_sprites: Res<GenericSprites>, cix_sprites: Res<CixSprites>,
+    settings: Res<Settings>,
 ) {
-    let (&timed, &global_transform) = spawn.single();
+    let Ok((&timed, &global_transform)) = spawn.get_single() else { return };
     let f = timed.fin();
 
     for (&particle, mut trns, mut sprite) in &mut particles {
-        trns.translation = particle.offset.lerp(Vec2::splat(0.), f * f).extend(0.);
+        trns.translation = particle.offset.lerp(Vec2::splat(0.), Easing::QuadIn.ease(f)).extend(0.);
         sprite.color = Color::NONE.lerp(Color::rgba(0.4, 1.8, 3., 0.36), f);
         sprite.custom_size = Some(Vec2::splat(0f32.lerp(particle.radius * 2., f)));
     }
 
     if timed.ended() {
-        crate::cix_spawn(&mut commands, &atlases, &generic_sprites, &cix_sprites, &atlas, global_transform);
+        crate::cix_spawn(&mut commands, &generic_sprites, &cix_sprites, settings.bindings.cix.clone(), global_transform);
         state.set(CixStates::Alive);
     }
 }
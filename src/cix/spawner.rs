@@ -8,8 +8,8 @@ use leafwing_input_manager::prelude::*;
 
 use crate::{
     GROUP_CIX,
-    GenericSprites, CixSprites, GameAtlas,
-    Cix, CixGrounded, CixLastGrounded, CixHovered, CixDirection, CixAction, CixJumpState,
+    GenericSprites, CixSprites,
+    Cix, CixGrounded, CixLastGrounded, CixHovered, CixDirection, CixAction, CixAim, CixJumpState,
     CixEye, CixAttire, CixArm, CixArmTarget,
     CixAttack, CixAttackState, CixLaserChargeParticle,
     Health,
@@ -17,8 +17,8 @@ use crate::{
 
 pub fn cix_spawn(
     commands: &mut Commands,
-    atlases: &Assets<TextureAtlas>,
-    generic_sprites: &GenericSprites, cix_sprites: &CixSprites, atlas: &GameAtlas,
+    generic_sprites: &GenericSprites, cix_sprites: &CixSprites,
+    input_map: InputMap<CixAction>,
     global_transform: GlobalTransform,
 ) {
     let group = CollisionGroups::new(GROUP_CIX, !GROUP_CIX);
@@ -36,11 +36,11 @@ pub fn cix_spawn(
         SpriteSheetBundle {
             sprite: TextureAtlasSprite {
                 color: *Cix::COLOR.start(),
-                index: atlas.index(atlases, &cix_sprites.head),
+                index: cix_sprites.head.index,
                 custom_size: Some(Vec2::splat(Cix::RADIUS.start() * 2.)),
                 ..default()
             },
-            texture_atlas: atlas.clone_weak(),
+            texture_atlas: cix_sprites.head.atlas.clone_weak(),
             transform: global_transform.into(),
             global_transform,
             ..default()
@@ -67,18 +67,10 @@ pub fn cix_spawn(
         ),
         (
             CixJumpState::default(),
+            CixAim::default(),
             InputManagerBundle {
                 action_state: default(),
-                input_map: InputMap::default()
-                    .insert(VirtualDPad {
-                        up: KeyCode::W.into(),
-                        down: KeyCode::S.into(),
-                        left: KeyCode::A.into(),
-                        right: KeyCode::D.into(),
-                    }, CixAction::Move)
-                    .insert(KeyCode::Space, CixAction::Jump)
-                    .insert(MouseButton::Left, CixAction::Attack)
-                    .build(),
+                input_map,
             },
         ),
     )).with_children(|builder| {
@@ -87,12 +79,12 @@ pub fn cix_spawn(
                 CixLaserChargeParticle::default(),
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(atlases, &generic_sprites.circle),
+                        index: generic_sprites.circle.index,
                         color: Color::NONE,
                         custom_size: Some(Vec2::splat(0.)),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: generic_sprites.circle.atlas.clone_weak(),
                     ..default()
                 },
             ));
@@ -103,10 +95,10 @@ pub fn cix_spawn(
             SpriteSheetBundle {
                 sprite: TextureAtlasSprite {
                     color: CixEye::COLOR,
-                    index: atlas.index(atlases, &cix_sprites.eye),
+                    index: cix_sprites.eye.index,
                     ..default()
                 },
-                texture_atlas: atlas.clone_weak(),
+                texture_atlas: cix_sprites.eye.atlas.clone_weak(),
                 transform: Transform::from_xyz(0., 0., 2.),
                 ..default()
             },
@@ -120,10 +112,10 @@ pub fn cix_spawn(
                 attire,
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(atlases, attire.sprite(cix_sprites)),
+                        index: attire.sprite(cix_sprites).index,
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: attire.sprite(cix_sprites).atlas.clone_weak(),
                     transform: Transform::from_translation(Vec2::new(offset.x, offset.y - CixAttire::OFFSET).extend(layer)),
                     ..default()
                 },
@@ -136,10 +128,7 @@ pub fn cix_spawn(
             let layer = if i == 0 { 5. } else { -0.01 };
 
             let (anchor_upper, anchor_lower) = arm.anchor();
-            let ((rect_upper, index_upper), (rect_lower, index_lower)) = {
-                let (sprite_upper, sprite_lower) = arm.sprites(cix_sprites);
-                (atlas.rect_index(atlases, sprite_upper), atlas.rect_index(atlases, sprite_lower))
-            };
+            let (sprite_upper, sprite_lower) = arm.sprites(cix_sprites);
 
             builder.spawn((
                 arm,
@@ -148,21 +137,21 @@ pub fn cix_spawn(
             )).with_children(|builder| {
                 builder.spawn(SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: index_upper,
-                        anchor: Anchor::Custom(Vec2::new(anchor_upper, 0.5) / rect_upper.size()),
+                        index: sprite_upper.index,
+                        anchor: Anchor::Custom(Vec2::new(anchor_upper, 0.5) / sprite_upper.size()),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprite_upper.atlas.clone_weak(),
                     ..default()
                 });
 
                 builder.spawn(SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: index_lower,
-                        anchor: Anchor::Custom(Vec2::new(anchor_lower, 0.5) / rect_lower.size()),
+                        index: sprite_lower.index,
+                        anchor: Anchor::Custom(Vec2::new(anchor_lower, 0.5) / sprite_lower.size()),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprite_lower.atlas.clone_weak(),
                     transform: Transform::from_xyz(arm_len, 0., 0.),
                     ..default()
                 });
@@ -7,7 +7,12 @@ use crate::{
     Health, Flower,
 };
 
+/// Keeps Cix from being killed by [`GROUP_STATIC`] colliders.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct GodMode(pub bool);
+
 pub fn collide_sys(
+    god: Res<GodMode>,
     end_state_now: Res<State<EndStates>>,
     mut end_state: ResMut<NextState<EndStates>>,
     mut events: EventReader<CollisionEvent>,
@@ -33,6 +38,7 @@ pub fn collide_sys(
                         None
                     }
                 } &&
+                    !(**god && group.memberships.contains(GROUP_CIX)) &&
                     other_group.memberships.contains(GROUP_STATIC) &&
                     group.memberships.intersects(other_group.filters) &&
                     other_group.memberships.intersects(group.filters)
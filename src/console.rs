@@ -0,0 +1,302 @@
+use bevy::{
+    prelude::*,
+    input::InputSystem,
+    window::PrimaryWindow,
+};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    Fonts,
+    GameStates,
+    EnvironmentSprites, GenericSprites, StaticEnemySprites,
+    Cix, CameraPos,
+    LdtkWorld,
+    WorldObject, GodMode, goto_level, spawn_flower,
+};
+
+/// A drop-down console toggled with the grave key, for jumping around levels and bending time while testing. Always
+/// built in debug builds; release builds need the `debug` feature.
+pub struct ConsolePlugin;
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DevConsole>()
+            .add_event::<ConsoleCommand>()
+
+            .add_system(console_spawn_sys.in_schedule(OnExit(GameStates::Loading)))
+            .add_system(console_input_sys
+                .in_base_set(CoreSet::PreUpdate)
+                .after(InputSystem)
+            )
+            .add_system(console_text_sys.run_if(resource_changed::<DevConsole>()))
+            .add_systems((
+                console_exec_sys,
+                console_spawn_object_sys,
+            ).distributive_run_if(in_state(GameStates::Gameplay)));
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct DevConsole {
+    pub open: bool,
+    pub input: String,
+    pub log: Vec<String>,
+}
+
+impl DevConsole {
+    pub const MAX_LOG: usize = 12;
+    pub const HELP: &'static str = "level <iid> | tp <x> <y> | god | spawn gear|barrier|flower | timescale <f> | pause | step";
+
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        if self.log.len() > Self::MAX_LOG {
+            self.log.remove(0);
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConsoleCommand {
+    Level(String),
+    Teleport(Vec2),
+    God,
+    Spawn(ConsoleObject),
+    TimeScale(f32),
+    Pause,
+    Step,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum ConsoleObject {
+    Gear,
+    Barrier,
+    Flower,
+}
+
+impl ConsoleCommand {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut args = line.split_whitespace();
+        let Some(name) = args.next() else { return Err("empty command".into()) };
+
+        let mut arg = |what: &str| args.next().ok_or_else(|| format!("{name}: missing {what}"));
+        let float = |value: &str| value.parse::<f32>().map_err(|e| format!("{name}: {value}: {e}"));
+
+        Ok(match name {
+            "level" => Self::Level(arg("level iid")?.into()),
+            "tp" => Self::Teleport(Vec2::new(float(arg("x")?)?, float(arg("y")?)?)),
+            "god" => Self::God,
+            "spawn" => Self::Spawn(match arg("object")? {
+                "gear" => ConsoleObject::Gear,
+                "barrier" => ConsoleObject::Barrier,
+                "flower" => ConsoleObject::Flower,
+                other => return Err(format!("spawn: unknown object '{other}'")),
+            }),
+            "timescale" => Self::TimeScale(float(arg("scale")?)?.max(0.)),
+            "pause" => Self::Pause,
+            "step" => Self::Step,
+            _ => return Err(format!("unknown command '{name}'; {}", DevConsole::HELP)),
+        })
+    }
+}
+
+#[derive(Component)]
+pub struct ConsoleRoot;
+#[derive(Component)]
+pub struct ConsoleText;
+
+pub fn console_spawn_sys(mut commands: Commands, fonts: Res<Fonts>) {
+    commands.spawn((
+        ConsoleRoot,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(0.),
+                    top: Val::Px(0.),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.), Val::Auto),
+                padding: UiRect::all(Val::Px(8.)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.8)),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+    )).with_children(|builder| { builder.spawn((
+        ConsoleText,
+        TextBundle::from_section("", TextStyle {
+            font: fonts.font.clone_weak(),
+            font_size: 16.,
+            color: Color::WHITE,
+        }),
+    )); });
+}
+
+/// While open, the console swallows all keyboard input so typing doesn't also move Cix around.
+pub fn console_input_sys(
+    mut console: ResMut<DevConsole>,
+    mut keys: ResMut<Input<KeyCode>>,
+    mut chars: EventReader<ReceivedCharacter>,
+    mut commands: EventWriter<ConsoleCommand>,
+) {
+    if keys.just_pressed(KeyCode::Grave) {
+        console.open = !console.open;
+        keys.reset_all();
+        chars.clear();
+        return;
+    }
+
+    if !console.open {
+        chars.clear();
+        return;
+    }
+
+    for &ReceivedCharacter { char, .. } in &mut chars {
+        if !char.is_control() && char != '`' {
+            console.input.push(char);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Back) {
+        console.input.pop();
+    }
+
+    if keys.just_pressed(KeyCode::Escape) {
+        console.open = false;
+    } else if keys.just_pressed(KeyCode::Return) {
+        let line = std::mem::take(&mut console.input);
+        console.print(format!("> {line}"));
+
+        match ConsoleCommand::parse(&line) {
+            Ok(command) => commands.send(command),
+            Err(e) => console.print(e),
+        }
+    }
+
+    keys.reset_all();
+}
+
+pub fn console_text_sys(
+    console: Res<DevConsole>,
+    mut root: Query<&mut Visibility, With<ConsoleRoot>>,
+    mut text: Query<&mut Text, With<ConsoleText>>,
+) {
+    for mut visibility in &mut root {
+        *visibility = if console.open { Visibility::Inherited } else { Visibility::Hidden };
+    }
+
+    for mut text in &mut text {
+        let value = &mut text.sections[0].value;
+        value.clear();
+        for line in &console.log {
+            value.push_str(line);
+            value.push('\n');
+        }
+
+        value.push_str("> ");
+        value.push_str(&console.input);
+        value.push('_');
+    }
+}
+
+pub fn console_exec_sys(
+    mut commands: Commands,
+    mut events: EventReader<ConsoleCommand>,
+    mut console: ResMut<DevConsole>,
+    mut god: ResMut<GodMode>,
+    mut time: ResMut<Time>, mut fixed_time: ResMut<FixedTime>,
+    mut camera_pos: ResMut<CameraPos>,
+    mut cix: Query<(&mut Transform, &mut Velocity), With<Cix>>,
+    world: Res<LdtkWorld>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut level: Query<&mut LevelSet>,
+    objects: Query<Entity, With<WorldObject>>,
+) {
+    for command in &mut events {
+        match *command {
+            ConsoleCommand::Level(ref iid) => {
+                // Going to a level that isn't there would tear the current one down for nothing.
+                let exists = ldtk_assets.get(&world.handle).map_or(false, |ldtk| ldtk.iter_levels().any(|level| level.iid == *iid));
+                if !exists {
+                    console.print(format!("No level with iid {iid}"));
+                    continue;
+                }
+
+                let Ok(mut level) = level.get_single_mut() else { continue };
+                goto_level(&mut commands, &mut level, &objects, iid.clone());
+                console.print(format!("Going to level {iid}"));
+            },
+            ConsoleCommand::Teleport(pos) => {
+                **camera_pos = pos;
+                if let Ok((mut trns, mut vel)) = cix.get_single_mut() {
+                    trns.translation = pos.extend(trns.translation.z);
+                    *vel = default();
+                }
+
+                console.print(format!("Teleported to ({}, {})", pos.x, pos.y));
+            },
+            ConsoleCommand::God => {
+                **god = !**god;
+                console.print(format!("God mode {}", if **god { "on" } else { "off" }));
+            },
+            ConsoleCommand::Spawn(_) => {},
+            ConsoleCommand::TimeScale(scale) => {
+                time.set_relative_speed(scale);
+                console.print(format!("Time scale {scale}"));
+            },
+            ConsoleCommand::Pause => {
+                if time.is_paused() {
+                    time.unpause();
+                    console.print("Unpaused");
+                } else {
+                    time.pause();
+                    console.print("Paused; 'step' advances one fixed step");
+                }
+            },
+            // The fixed schedule only runs off accumulated time, which doesn't accumulate while paused.
+            ConsoleCommand::Step => {
+                let period = fixed_time.period;
+                fixed_time.tick(period);
+            },
+        }
+    }
+}
+
+/// Spawns objects under the cursor, with the same defaults the LDtk entities have.
+pub fn console_spawn_object_sys(
+    mut commands: Commands,
+    mut events: EventReader<ConsoleCommand>,
+    mut console: ResMut<DevConsole>,
+    window: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform)>,
+    env_sprites: Res<EnvironmentSprites>, gen_sprites: Res<GenericSprites>, enemy_sprites: Res<StaticEnemySprites>,
+) {
+    for command in &mut events {
+        let &ConsoleCommand::Spawn(object) = command else { continue };
+        let Some(pos) = window.get_single().ok()
+            .and_then(|window| window.cursor_position())
+            .and_then(|cursor| {
+                let (camera, camera_trns) = camera.get_single().ok()?;
+                camera.viewport_to_world_2d(camera_trns, cursor)
+            })
+        else {
+            console.print("spawn: the cursor isn't over the window");
+            continue;
+        };
+
+        match object {
+            ConsoleObject::Gear => {
+                crate::spawn_enemy_gear(&mut commands, 2., Color::WHITE, None, pos, &enemy_sprites);
+            },
+            ConsoleObject::Barrier => {
+                crate::spawn_enemy_barrier(&mut commands, 4., Color::WHITE, pos, &enemy_sprites);
+            },
+            ConsoleObject::Flower => {
+                spawn_flower(&mut commands, &env_sprites, &gen_sprites, pos);
+            },
+        }
+
+        console.print(format!("Spawned {object:?} at ({:.0}, {:.0})", pos.x, pos.y));
+    }
+}
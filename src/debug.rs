@@ -0,0 +1,133 @@
+use bevy::{
+    prelude::*,
+    diagnostic::{
+        Diagnostics, FrameTimeDiagnosticsPlugin,
+    },
+};
+use bevy_rapier2d::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::{
+    CameraAction, Fonts,
+    GameStates,
+    Cix, CixGrounded, CixHovered, CixLastGrounded, CixJumpState,
+    CixFire, CixParticle, CixDeathParticle, CixSpawnParticle,
+    EnemyBarrierParticle,
+    WorldObject,
+    Timed, GameTime,
+};
+
+use std::fmt::Write;
+
+/// Rapier's collider wireframes plus a text overlay of frame rate, entity counts and Cix's physics state, toggled
+/// with [`CameraAction::ToggleDebug`]. Always built in debug builds; release builds need the `debug` feature.
+pub struct DebugPlugin;
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugin(FrameTimeDiagnosticsPlugin)
+            .add_plugin(RapierDebugRenderPlugin {
+                enabled: false,
+                ..default()
+            })
+
+            .add_system(debug_spawn_sys.in_schedule(OnExit(GameStates::Loading)))
+            .add_system(debug_toggle_sys)
+            .add_system(debug_text_sys);
+    }
+}
+
+#[derive(Component)]
+pub struct DebugOverlay;
+
+pub fn debug_spawn_sys(mut commands: Commands, fonts: Res<Fonts>) {
+    commands.spawn((
+        DebugOverlay,
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(8.),
+                    top: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_section("", TextStyle {
+                font: fonts.font.clone_weak(),
+                font_size: 16.,
+                color: Color::rgb(0.4, 1., 0.4),
+            })
+        },
+    ));
+}
+
+pub fn debug_toggle_sys(
+    mut context: ResMut<DebugRenderContext>,
+    camera: Query<&ActionState<CameraAction>, With<Camera>>,
+    mut overlay: Query<&mut Visibility, With<DebugOverlay>>,
+) {
+    let Ok(input) = camera.get_single() else { return };
+    if !input.just_pressed(CameraAction::ToggleDebug) { return };
+
+    context.enabled = !context.enabled;
+    for mut visibility in &mut overlay {
+        *visibility = if context.enabled { Visibility::Inherited } else { Visibility::Hidden };
+    }
+}
+
+pub fn debug_text_sys(
+    context: Res<DebugRenderContext>,
+    diagnostics: Res<Diagnostics>, time: Res<GameTime>,
+    mut overlay: Query<&mut Text, With<DebugOverlay>>,
+    cix: Query<(&CixGrounded, &CixHovered, &CixLastGrounded, &CixJumpState, &Velocity), With<Cix>>,
+    entities: Query<()>,
+    objects: Query<(), With<WorldObject>>,
+    timed: Query<(), With<Timed>>,
+    fires: Query<(), With<CixFire>>,
+    particles: Query<(), With<CixParticle>>,
+    death_particles: Query<(), With<CixDeathParticle>>,
+    spawn_particles: Query<(), With<CixSpawnParticle>>,
+    barrier_particles: Query<(), With<EnemyBarrierParticle>>,
+) {
+    if !context.enabled { return };
+    let Ok(mut text) = overlay.get_single_mut() else { return };
+
+    let value = &mut text.sections[0].value;
+    value.clear();
+
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.smoothed())
+        .unwrap_or(0.);
+    let _ = writeln!(value, "FPS: {fps:.1}");
+    let _ = writeln!(value, "Time: {:.2}s ({} ticks)", time.elapsed_seconds_f64(), time.ticks);
+    let _ = writeln!(value);
+
+    for (name, count) in [
+        ("Entities", entities.iter().count()),
+        ("WorldObject", objects.iter().count()),
+        ("Timed", timed.iter().count()),
+        ("CixFire", fires.iter().count()),
+        ("CixParticle", particles.iter().count()),
+        ("CixDeathParticle", death_particles.iter().count()),
+        ("CixSpawnParticle", spawn_particles.iter().count()),
+        ("EnemyBarrierParticle", barrier_particles.iter().count()),
+    ] {
+        let _ = writeln!(value, "{name}: {count}");
+    }
+
+    let _ = writeln!(value);
+    if let Ok((&grounded, &hovered, &last_grounded, &jump, &vel)) = cix.get_single() {
+        let _ = writeln!(value, "Grounded: {}", *grounded);
+        let _ = writeln!(value, "Hovered: {}", *hovered);
+        let _ = writeln!(value, "Last grounded: {:?}", *last_grounded);
+        let _ = writeln!(value, "Jump: {:?}", jump.jump_time);
+        let _ = writeln!(value, "Last jump: {:?}", jump.last_jump_time);
+        let _ = writeln!(value, "Jump buffer: {:?}", jump.buffer_time);
+        let _ = writeln!(value, "Velocity: ({:.1}, {:.1}), {:.2} rad/s", vel.linvel.x, vel.linvel.y, vel.angvel);
+    } else {
+        let _ = writeln!(value, "No Cix");
+    }
+}
@@ -6,11 +6,11 @@ use rand::{
 };
 
 use crate::{
-    ext::*,
     GROUP_STATIC, GROUP_STOP_PIERCE, GROUP_GROUND,
-    GenericSprites, StaticEnemySprites, GameAtlas,
+    GenericSprites, StaticEnemySprites,
     WorldObject,
-    Timed,
+    Timed, Tween, Keyframes, Easing,
+    GameRng,
 };
 
 use std::ops::RangeInclusive as RangeIncl;
@@ -28,17 +28,13 @@ impl EnemyBarrier {
 }
 
 #[derive(Component, Copy, Clone)]
-pub struct EnemyBarrierParticle {
-    pub init: f32,
-    pub height: f32,
-}
+pub struct EnemyBarrierParticle;
 
 pub fn spawn_enemy_barrier(
     commands: &mut Commands,
     height: f32, color: Color,
     pos: Vec2,
-    atlases: &Assets<TextureAtlas>,
-    enemy_sprites: &StaticEnemySprites, atlas: &GameAtlas,
+    enemy_sprites: &StaticEnemySprites,
 ) {
     let height = height * 32.;
     commands.spawn((
@@ -50,8 +46,8 @@ pub fn spawn_enemy_barrier(
             Collider::cuboid(16., 16.),
         ),
         SpriteSheetBundle {
-            sprite: TextureAtlasSprite::new(atlas.index(atlases, &enemy_sprites.barrier)),
-            texture_atlas: atlas.clone_weak(),
+            sprite: TextureAtlasSprite::new(enemy_sprites.barrier.index),
+            texture_atlas: enemy_sprites.barrier.atlas.clone_weak(),
             transform: Transform::from_translation(pos.extend(10.)),
             ..default()
         },
@@ -63,47 +59,40 @@ pub fn spawn_enemy_barrier(
 }
 
 pub fn enemy_barrier_update_sys(
-    mut commands: Commands, time: Res<Time>,
+    mut commands: Commands, mut rng: ResMut<GameRng>,
     barriers: Query<(&EnemyBarrier, &GlobalTransform)>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<GenericSprites>,
 ){
-    let mut rng = thread_rng();
+    let rng = &mut **rng;
     let chance = Uniform::from(0f32..=1f32);
     let height = Uniform::from(0.75f32..=1f32);
     let start = Uniform::from(-16f32..=16f32);
     let radius = Uniform::from(EnemyBarrier::RADIUS);
 
     for (&barrier, &global_trns) in &barriers {
-        if chance.sample(&mut rng) <= EnemyBarrier::CHANCE * time.delta_seconds() * 60. {
+        if chance.sample(rng) <= EnemyBarrier::CHANCE {
             let pos = global_trns.translation();
+            let init = Vec2::new(pos.x + start.sample(rng), pos.y);
+            let rise = barrier.height * height.sample(rng) + 32.;
             commands.spawn((
                 WorldObject,
-                EnemyBarrierParticle {
-                    init: pos.y,
-                    height: barrier.height * height.sample(&mut rng) + 32.,
-                },
+                EnemyBarrierParticle,
                 Timed::new(EnemyBarrier::TIME * (barrier.height as f64 / 320.)),
+                Tween::new()
+                    .translation(Keyframes::between(init, init + Vec2::new(0., rise), Easing::QuadOut))
+                    .color(Keyframes::between(barrier.color.with_a(1.), barrier.color.with_a(0.), Easing::QuadOut)),
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.circle),
+                        index: sprites.circle.index,
                         color: barrier.color,
-                        custom_size: Some(Vec2::splat(radius.sample(&mut rng) * 2.)),
+                        custom_size: Some(Vec2::splat(radius.sample(rng) * 2.)),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
-                    transform: Transform::from_xyz(pos.x + start.sample(&mut rng), pos.y + 16., pos.z + 5.),
+                    texture_atlas: sprites.circle.atlas.clone_weak(),
+                    transform: Transform::from_xyz(init.x, init.y + 16., pos.z + 5.),
                     ..default()
                 },
             ));
         }
     }
 }
-
-pub fn enemy_barrier_particle_update_sys(mut particles: Query<(&EnemyBarrierParticle, &Timed, &mut Transform, &mut TextureAtlasSprite)>) {
-    for (&particle, &timed, mut trns, mut sprite) in &mut particles {
-        let f = timed.fin();
-        trns.translation.y = particle.init.lerp(particle.init + particle.height, 1. - (f - 1.) * (f - 1.));
-        sprite.color.set_a((f - 1.) * (f - 1.));
-    }
-}
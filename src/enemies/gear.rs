@@ -8,7 +8,7 @@ use bevy_rapier2d::prelude::*;
 use crate::{
     ext::*,
     GROUP_STATIC, GROUP_STOP_PIERCE,
-    GenericSprites, StaticEnemySprites, GameAtlas,
+    GenericSprites, StaticEnemySprites,
     WorldObject,
 };
 
@@ -31,8 +31,7 @@ pub fn spawn_enemy_gear(
     diameter: f32, color: Color,
     reference: Option<String>,
     pos: Vec2,
-    atlases: &Assets<TextureAtlas>,
-    enemy_sprites: &StaticEnemySprites, atlas: &GameAtlas,
+    enemy_sprites: &StaticEnemySprites,
 ) -> Entity {
     commands.spawn((
         WorldObject,
@@ -48,12 +47,12 @@ pub fn spawn_enemy_gear(
         ),
         SpriteSheetBundle {
             sprite: TextureAtlasSprite {
-                index: atlas.index(atlases, &enemy_sprites.gear),
+                index: enemy_sprites.gear.index,
                 custom_size: Some(Vec2::splat(diameter * 32.)),
                 color,
                 ..default()
             },
-            texture_atlas: atlas.clone_weak(),
+            texture_atlas: enemy_sprites.gear.atlas.clone_weak(),
             transform: Transform::from_translation(pos.extend(20.)),
             ..default()
         },
@@ -67,19 +66,18 @@ pub fn enemy_gear_init_sys(
         Query<(Entity, &mut EnemyGear, &TextureAtlasSprite), Added<EnemyGear>>,
         Query<(&EnemyGear, &TextureAtlasSprite, &GlobalTransform)>,
     )>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<GenericSprites>,
 ) {
     let mut links = Vec::new();
     for (e, mut gear, sprite) in &mut set.p0() {
         commands.entity(e).with_children(|builder| { builder.spawn(SpriteSheetBundle {
             sprite: TextureAtlasSprite {
-                index: atlas.index(&atlases, &sprites.circle),
+                index: sprites.circle.index,
                 color: sprite.color,
                 custom_size: Some(Vec2::splat(gear.radius * 16.)),
                 ..default()
             },
-            texture_atlas: atlas.clone_weak(),
+            texture_atlas: sprites.circle.atlas.clone_weak(),
             ..default()
         }); });
 
@@ -109,13 +107,13 @@ pub fn enemy_gear_init_sys(
                 WorldObject,
                 SpriteSheetBundle {
                     sprite: TextureAtlasSprite {
-                        index: atlas.index(&atlases, &sprites.square),
+                        index: sprites.square.index,
                         color: from_sprite.color.lerp(to_sprite.color, 0.5).with_a(0.5),
                         anchor: Anchor::Custom(Vec2::new(-0.5, 0.)),
                         custom_size: Some(Vec2::new((end - start).length(), 4.5)),
                         ..default()
                     },
-                    texture_atlas: atlas.clone_weak(),
+                    texture_atlas: sprites.square.atlas.clone_weak(),
                     transform: Transform::from_translation(start.extend(from_trns.z))
                         .with_rotation(Quat::from_axis_angle(Vec3::Z, Vec2::X.angle_between(end - start))),
                     ..default()
@@ -142,12 +140,8 @@ pub fn enemy_gear_init_sys(
     }
 }
 
-pub fn enemy_gear_update_sys(
-    time: Res<Time>,
-    mut gears: Query<(&EnemyGear, &mut Transform), With<EnemyGear>>,
-) {
-    let delta = time.delta_seconds() * 60.;
+pub fn enemy_gear_update_sys(mut gears: Query<(&EnemyGear, &mut Transform), With<EnemyGear>>) {
     for (gear, mut trns) in &mut gears {
-        trns.rotation *= Quat::from_axis_angle(Vec3::Z, (delta * EnemyGear::ROTATE_SPEED / gear.radius).to_radians());
+        trns.rotation *= Quat::from_axis_angle(Vec3::Z, (EnemyGear::ROTATE_SPEED / gear.radius).to_radians());
     }
 }
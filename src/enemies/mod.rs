@@ -1,5 +1,32 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameStates, FixedSet,
+};
+
 mod barrier;
 mod gear;
 
 pub use barrier::*;
 pub use gear::*;
+
+pub struct EnemiesPlugin;
+impl Plugin for EnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .insert_resource(EnemyGears::default())
+
+            .add_system(enemy_gear_init_sys
+                .in_base_set(CoreSet::PreUpdate)
+                .run_if(in_state(GameStates::Gameplay))
+            )
+            .add_systems((
+                enemy_gear_update_sys,
+                enemy_barrier_update_sys,
+            )
+                .in_base_set(FixedSet::Update)
+                .distributive_run_if(in_state(GameStates::Gameplay))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            );
+    }
+}
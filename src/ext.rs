@@ -165,3 +165,17 @@ impl LerpExt for Vec2 {
         self + (dest - self) * f
     }
 }
+
+impl LerpExt for Vec3 {
+    #[inline]
+    fn lerp(self, dest: Self, f: f32) -> Self {
+        self + (dest - self) * f
+    }
+}
+
+impl LerpExt for Quat {
+    #[inline]
+    fn lerp(self, dest: Self, f: f32) -> Self {
+        self.slerp(dest, f)
+    }
+}
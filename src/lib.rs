@@ -0,0 +1,136 @@
+#![feature(let_chains)]
+
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::type_complexity)]
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use include_optional::include_str_optional;
+
+pub mod ext;
+
+mod animation;
+mod assets;
+mod camera;
+mod collide;
+#[cfg(any(debug_assertions, feature = "debug"))]
+mod console;
+#[cfg(any(debug_assertions, feature = "debug"))]
+mod debug;
+mod enemies;
+mod health;
+mod cix;
+mod pack;
+mod replay;
+mod rng;
+mod save;
+mod settings;
+mod sim;
+mod speedrun;
+mod timed;
+mod tween;
+mod ui;
+mod world;
+
+pub use animation::*;
+pub use assets::*;
+pub use camera::*;
+pub use collide::*;
+#[cfg(any(debug_assertions, feature = "debug"))]
+pub use console::*;
+#[cfg(any(debug_assertions, feature = "debug"))]
+pub use debug::*;
+pub use enemies::*;
+pub use health::*;
+pub use cix::*;
+pub use pack::*;
+pub use replay::*;
+pub use rng::*;
+pub use save::*;
+pub use settings::*;
+pub use sim::*;
+pub use speedrun::*;
+pub use timed::*;
+pub use tween::*;
+pub use ui::*;
+pub use world::*;
+
+pub const PIXELS_PER_METER: f32 = 100.;
+
+pub const GROUP_CIX: Group = Group::GROUP_1;
+pub const GROUP_ENEMY: Group = Group::GROUP_2;
+pub const GROUP_STATIC: Group = Group::GROUP_3;
+pub const GROUP_GATE: Group = Group::GROUP_4;
+pub const GROUP_CHECKPOINT: Group = Group::GROUP_5;
+pub const GROUP_SIGN: Group = Group::GROUP_6;
+pub const GROUP_BULLET: Group = Group::GROUP_30;
+pub const GROUP_STOP_PIERCE: Group = Group::GROUP_31;
+pub const GROUP_GROUND: Group = Group::GROUP_32;
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default, States)]
+pub enum GameStates {
+    #[default]
+    Loading,
+    MainMenu,
+    Prelude,
+    Gameplay,
+    Ending,
+}
+
+/// Base sets of [`CoreSchedule::FixedUpdate`], where all gameplay runs. Rapier's [`PhysicsSet`]s sit between
+/// [`FixedSet::UpdateFlush`] and [`FixedSet::PostUpdate`].
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[system_set(base)]
+pub enum FixedSet {
+    First,
+    PreUpdate,
+    PreUpdateFlush,
+    Update,
+    UpdateFlush,
+    PostUpdate,
+    PostUpdateFlush,
+    StateTransitions,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default, States)]
+pub enum EndStates {
+    #[default]
+    No,
+    Yes,
+    Done,
+}
+
+/// While [`PauseStates::Paused`], none of the [`FixedSet`]s or [`PhysicsSet`]s run, so the world stays exactly as it
+/// was when paused.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default, States)]
+pub enum PauseStates {
+    #[default]
+    Running,
+    Paused,
+}
+
+pub const MESSAGE: Option<&'static str> = include_str_optional!("message.txt");
+
+/// The whole game, minus the windowing and rendering plugins supplied by the binary. Headless apps should add
+/// [`AssetsPlugin`], [`WorldPlugin`], [`CixPlugin`] and [`EnemiesPlugin`] individually instead.
+pub struct CixGamePlugin;
+impl Plugin for CixGamePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugin(SettingsPlugin)
+            .add_plugin(SavePlugin)
+            .add_plugin(AssetsPlugin)
+            .add_plugin(WorldPlugin)
+            .add_plugin(CixPlugin)
+            .add_plugin(EnemiesPlugin)
+            .add_plugin(CameraPlugin)
+            .add_plugin(UiPlugin)
+            .add_plugin(SpeedrunPlugin);
+
+        #[cfg(any(debug_assertions, feature = "debug"))]
+        app
+            .add_plugin(DebugPlugin)
+            .add_plugin(ConsolePlugin);
+    }
+}
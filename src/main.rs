@@ -1,224 +1,93 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
-#![feature(let_chains)]
-
-#![allow(clippy::too_many_arguments)]
-#![allow(clippy::type_complexity)]
 
 use bevy::{
     prelude::*,
     asset::AssetPlugin,
     core_pipeline::clear_color::ClearColor,
-    render::camera::CameraUpdateSystem,
-    window::{
-        WindowResolution,
-        PresentMode,
-    },
 };
 
-use bevy_asset_loader::prelude::*;
-use bevy_ecs_ldtk::prelude::*;
+#[cfg(not(feature = "dev"))]
 use bevy_embedded_assets::EmbeddedAssetPlugin;
-use bevy_rapier2d::prelude::*;
-use iyes_progress::prelude::*;
-use leafwing_input_manager::prelude::*;
-
-use include_optional::include_str_optional;
-
-pub mod ext;
-
-mod assets;
-mod camera;
-mod collide;
-mod enemies;
-mod health;
-mod cix;
-mod timed;
-mod world;
-
-pub use assets::*;
-pub use camera::*;
-pub use collide::*;
-pub use enemies::*;
-pub use health::*;
-pub use cix::*;
-pub use timed::*;
-pub use world::*;
-
-pub const PIXELS_PER_METER: f32 = 100.;
-
-pub const GROUP_CIX: Group = Group::GROUP_1;
-pub const GROUP_ENEMY: Group = Group::GROUP_2;
-pub const GROUP_STATIC: Group = Group::GROUP_3;
-pub const GROUP_GATE: Group = Group::GROUP_4;
-pub const GROUP_BULLET: Group = Group::GROUP_30;
-pub const GROUP_STOP_PIERCE: Group = Group::GROUP_31;
-pub const GROUP_GROUND: Group = Group::GROUP_32;
-
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default, States)]
-pub enum GameStates {
-    #[default]
-    Loading,
-    Prelude,
-    Gameplay,
-    Ending,
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default, States)]
-pub enum EndStates {
-    #[default]
-    No,
-    Yes,
-    Done,
-}
-
-pub const PRELUDE: &'static str = include_str!("prelude.txt");
-pub const MESSAGE: Option<&'static str> = include_str_optional!("message.txt");
 
-fn main() {
-    App::new()
-        .add_state::<GameStates>()
-        .add_state::<EndStates>()
-        .add_state::<CixStates>()
-        .add_loading_state(LoadingState::new(GameStates::Loading))
+use cix::{
+    CixGamePlugin,
+    CixReplay, ReplayPlugin, ReplayMode,
+    Settings,
+};
 
-        .add_collection_to_loading_state::<_, Fonts>(GameStates::Loading)
-        .add_collection_to_loading_state::<_, LdtkWorld>(GameStates::Loading)
-        .add_collection_to_loading_state::<_, BackgroundImages>(GameStates::Loading)
-        .add_collection_to_loading_state::<_, EnvironmentSprites>(GameStates::Loading)
-        .add_collection_to_loading_state::<_, GenericSprites>(GameStates::Loading)
-        .add_collection_to_loading_state::<_, CixSprites>(GameStates::Loading)
-        .add_collection_to_loading_state::<_, StaticEnemySprites>(GameStates::Loading)
-        .init_resource_after_loading_state::<_, GameAtlas>(GameStates::Loading)
+use std::{
+    env, fs,
+    process::ExitCode,
+};
 
-        .insert_resource(ClearColor(Color::NONE))
-        .insert_resource(Msaa::Off)
-        .insert_resource(RapierConfiguration {
-            gravity: Vec2::new(0., -9.81 * PIXELS_PER_METER),
-            timestep_mode: TimestepMode::Variable {
-                max_dt: 1.0 / 20.0,
-                time_scale: 1.0,
-                substeps: 1,
+/// Pass `--record <file>` to record Cix's input, or `--replay <file>` to play a recording back.
+fn main() -> ExitCode {
+    let mut replay = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mode = match (arg.as_str(), args.next()) {
+            ("--record", Some(path)) => ReplayMode::Record(path.into()),
+            ("--replay", Some(path)) => match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|source| CixReplay::parse(&source)) {
+                Ok(replay) => ReplayMode::Replay(replay),
+                Err(e) => {
+                    eprintln!("{path}: {e}");
+                    return ExitCode::from(2);
+                },
             },
+            _ => {
+                eprintln!("Usage: cix [--record <file> | --replay <file>]");
+                return ExitCode::from(2);
+            },
+        };
+
+        replay = Some(mode);
+    }
+
+    let settings = Settings::load().unwrap_or_else(|e| {
+        eprintln!("{e}; using the default settings");
+        Settings::default()
+    });
+
+    let plugins = DefaultPlugins
+        .set(ImagePlugin::default_linear())
+        .set(WindowPlugin {
+            primary_window: Some(Window {
+                title: "Cix".into(),
+                resolution: settings.window.resolution(),
+                mode: settings.window.mode(),
+                present_mode: settings.window.present_mode(),
+                ..default()
+            }),
             ..default()
-        })
+        });
 
-        .insert_resource(LdtkSettings {
-            set_clear_color: SetClearColor::No,
-            int_grid_rendering: IntGridRendering::Invisible,
-            level_background: LevelBackground::Nonexistent,
+    // Development builds read `assets/` straight off the disk, so edits show up without rebuilding.
+    #[cfg(feature = "dev")]
+    let plugins = plugins
+        .set(AssetPlugin {
+            watch_for_changes: true,
             ..default()
         })
+        .build();
+    #[cfg(not(feature = "dev"))]
+    let plugins = plugins
+        .build()
+        .add_before::<AssetPlugin, _>(EmbeddedAssetPlugin);
+
+    let mut app = App::new();
+    app
+        .insert_resource(ClearColor(Color::NONE))
+        .insert_resource(Msaa::Off)
 
-        .insert_resource(CameraPos(Vec2::splat(0.)))
-        .insert_resource(CixSpawnPos(Vec2::splat(0.)))
-        .insert_resource(EnemyGears::default())
-
-        .add_plugins(DefaultPlugins
-            .set(ImagePlugin::default_linear())
-            .set(WindowPlugin {
-                primary_window: Some(Window {
-                    title: "Cix".into(),
-                    resolution: WindowResolution::new(800., 500.),
-                    present_mode: PresentMode::AutoNoVsync,
-                    ..default()
-                }),
-                ..default()
-            })
-            .build()
-            .add_before::<AssetPlugin, _>(EmbeddedAssetPlugin)
-        )
-
-        .add_plugin(ProgressPlugin::new(GameStates::Loading).continue_to(GameStates::Prelude))
-        .add_plugin(InputManagerPlugin::<CixAction>::default())
-        .add_plugin(InputManagerPlugin::<CameraAction>::default())
-        .add_plugin(LdtkPlugin)
-        .add_plugin(RapierPhysicsPlugin::<()>::pixels_per_meter(PIXELS_PER_METER))
-
-        .add_event::<DeathEvent>()
-
-        .add_startup_systems((
-            camera_spawn_sys,
-            camera_viewport_sys
-                .in_base_set(StartupSet::PostStartup)
-                .before(CameraUpdateSystem),
-        ))
-        .add_system(camera_viewport_sys
-            .in_base_set(CoreSet::PostUpdate)
-            .before(CameraUpdateSystem)
-        )
-        .add_system(camera_toggle_bloom_sys)
-
-        .add_system(on_end_sys.in_schedule(OnEnter(EndStates::Yes)))
-        .add_system(end_update_sys.in_set(OnUpdate(EndStates::Yes)))
-
-        .add_system(game_end_enter_sys.in_schedule(OnEnter(GameStates::Ending)))
-        .add_system(game_end_update_sys.in_set(OnUpdate(GameStates::Ending)))
-
-        .add_systems((timed_update_sys, health_update_sys).in_base_set(CoreSet::PreUpdate))
-        .add_systems((timed_post_update_sys, health_post_update_sys).in_base_set(CoreSet::PostUpdate))
-
-        .add_system(prelude_enter_sys.in_schedule(OnEnter(GameStates::Prelude)))
-        .add_system(prelude_update_sys.in_set(OnUpdate(GameStates::Prelude)))
-        .add_system(prelude_exit_sys.in_schedule(OnExit(GameStates::Prelude)))
-
-        .add_systems((world_start_sys, world_fade_add_sys).in_schedule(OnEnter(GameStates::Gameplay)))
-        .add_system(world_post_start_sys
-            .in_base_set(CoreSet::PreUpdate)
-            .run_if(in_state(GameStates::Gameplay))
-        )
-        .add_systems((
-            world_fade_update_sys,
-            world_update_bg_sys,
-            collide_sys,
-        ).in_set(OnUpdate(GameStates::Gameplay)))
-        .add_system(world_start_update_sys
-            .run_if(in_state(GameStates::Gameplay))
-            .run_if(in_state(CixStates::Nonexistent))
-        )
-
-        .add_systems((cix_pre_update_sys, cix_follow_camera_sys)
-            .in_base_set(CoreSet::PreUpdate)
-            .distributive_run_if(in_state(CixStates::Alive))
-        )
+        .add_plugins(plugins)
 
-        .add_system(cix_init_spawn_sys.in_schedule(OnEnter(CixStates::Spawning)))
-        .add_system(cix_update_spawn_sys.in_set(OnUpdate(CixStates::Spawning)))
-        .add_systems((
-            cix_update_fire_sys,
-            cix_update_particle_sys,
-            cix_attack_update_sys,
-            cix_update_death_sys,
-        ).in_set(OnUpdate(GameStates::Gameplay)))
-        .add_systems((
-            cix_check_alive_sys,
-            cix_update_sys,
-            cix_update_head_sys,
-            cix_spawn_particle_sys.after(cix_update_head_sys),
-            cix_flip_direction_sys,
-            cix_update_direction_sys.after(cix_flip_direction_sys),
-            cix_direct_attire_sys.after(cix_update_direction_sys),
-            cix_update_arm_sys,
-            cix_spawn_fire_sys,
-            cix_update_eye_sys,
-        ).in_set(OnUpdate(CixStates::Alive)))
-        .add_systems((
-            cix_move_sys,
-            cix_jump_sys,
-            cix_attack_input_sys,
-            cix_attack_sys.after(cix_attack_input_sys),
-            update_gate_sys,
-            update_flower_sys,
-        ).in_set(OnUpdate(CixStates::Alive)))
-        .add_system(cix_respawn_sys.in_set(OnUpdate(CixStates::Dead)))
+        .insert_resource(settings)
+        .add_plugin(CixGamePlugin);
 
-        .add_system(enemy_gear_init_sys
-            .in_base_set(CoreSet::PreUpdate)
-            .run_if(in_state(GameStates::Gameplay))
-        )
-        .add_systems((
-            enemy_gear_update_sys,
-            enemy_barrier_update_sys, enemy_barrier_particle_update_sys,
-        ).in_set(OnUpdate(GameStates::Gameplay)))
+    if let Some(mode) = replay {
+        app.add_plugin(ReplayPlugin { mode });
+    }
 
-        .run();
+    app.run();
+    ExitCode::SUCCESS
 }
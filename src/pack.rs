@@ -0,0 +1,370 @@
+#[cfg(feature = "dev")]
+use bevy::prelude::*;
+use image::{
+    ImageOutputFormat, Rgba, RgbaImage,
+    imageops,
+};
+use serde::Deserialize;
+
+use crate::{
+    ATLAS_PAD,
+    AtlasLayout, AtlasLayoutPage, AtlasLayoutSprite, AtlasLayoutAnimation, GameAtlas,
+};
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    io::Cursor,
+    path::{
+        Path, PathBuf,
+    },
+};
+#[cfg(feature = "dev")]
+use std::time::SystemTime;
+
+pub const SPRITES: &str = "sprites";
+const PADDING: &str = "sprites/padding.ron";
+const ANIMATION_EXT: &str = ".anim.ron";
+pub const ATLAS: &str = "assets/atlas";
+const PAGE_SIZE: u32 = 2048;
+
+/// What fills a sprite's [`ATLAS_PAD`] border.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Deserialize)]
+pub enum AtlasPadding {
+    Transparent,
+    /// Repeats the nearest edge pixel, so stretched and tiled sprites don't get fringes where they're sampled.
+    #[default]
+    Extrude,
+}
+
+/// Copies `image` into the middle of a canvas `pad` pixels larger on every side, filling the border per `mode`.
+pub fn pad_sprite(image: &RgbaImage, (pad_x, pad_y): (u32, u32), mode: AtlasPadding) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let mut canvas = RgbaImage::new(width + pad_x * 2, height + pad_y * 2);
+    if width == 0 || height == 0 { return canvas };
+
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        let (src_x, src_y) = (x as i64 - pad_x as i64, y as i64 - pad_y as i64);
+        let inside = (0..width as i64).contains(&src_x) && (0..height as i64).contains(&src_y);
+
+        *pixel = match mode {
+            _ if inside => *image.get_pixel(src_x as u32, src_y as u32),
+            AtlasPadding::Transparent => Rgba([0; 4]),
+            AtlasPadding::Extrude => *image.get_pixel(
+                src_x.clamp(0, width as i64 - 1) as u32,
+                src_y.clamp(0, height as i64 - 1) as u32,
+            ),
+        };
+    }
+
+    canvas
+}
+
+/// The sprites in [`PADDING`] that aren't [`AtlasPadding::Extrude`]d, if there's such a file.
+fn paddings() -> Result<HashMap<String, AtlasPadding>, String> {
+    let path = Path::new(PADDING);
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let source = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    ron::from_str(&source).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Every PNG and animation under `dir`, sorted so the packing doesn't depend on the file system's order.
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let mut entries = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {e}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {e}", dir.display()))?;
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            collect(&path, files)?;
+        } else if path.extension().map_or(false, |ext| ext == "png") || is_animation(&path) {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn is_animation(path: &Path) -> bool {
+    path.file_name().and_then(|name| name.to_str()).map_or(false, |name| name.ends_with(ANIMATION_EXT))
+}
+
+/// Reads every animation, making sure each frame is a sprite in `layout` and lasts a while.
+fn animations(files: &[PathBuf], layout: &mut AtlasLayout) -> Result<(), String> {
+    for path in files {
+        let source = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let animation = ron::from_str::<AtlasLayoutAnimation>(&source).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        if animation.frames.is_empty() {
+            return Err(format!("{}: no frames", path.display()));
+        }
+
+        for frame in &animation.frames {
+            if !layout.sprites.contains_key(&frame.sprite) {
+                return Err(format!("{}: no sprite named {}", path.display(), frame.sprite));
+            }
+
+            if frame.duration <= 0. {
+                return Err(format!("{}: {} lasts {}s", path.display(), frame.sprite, frame.duration));
+            }
+        }
+
+        if let Some(&index) = animation.events.keys().find(|&&index| index >= animation.frames.len()) {
+            return Err(format!("{}: event on frame {index}, past the last frame", path.display()));
+        }
+
+        // `sprite_name` only strips the `.ron`.
+        let name = GameAtlas::sprite_name(path);
+        let name = name.strip_suffix(".anim").unwrap_or(&name).to_string();
+        layout.animations.insert(name, animation);
+    }
+
+    Ok(())
+}
+
+/// Shelf-packs the sprites, tallest first, starting a new page whenever one fills up.
+pub fn pack_atlas() -> Result<(AtlasLayout, Vec<RgbaImage>), String> {
+    let mut files = Vec::new();
+    collect(Path::new(SPRITES), &mut files)?;
+    let (animation_files, files) = files.into_iter().partition::<Vec<_>, _>(|path| is_animation(path));
+    let paddings = paddings()?;
+
+    let mut sprites = files.into_iter()
+        .map(|path| {
+            let image = image::open(&path).map_err(|e| format!("{}: {e}", path.display()))?.into_rgba8();
+            Ok((GameAtlas::sprite_name(&path), image))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    sprites.sort_by_key(|(name, image)| (Reverse(image.height()), Reverse(image.width()), name.clone()));
+
+    let (pad_x, pad_y) = (ATLAS_PAD.0 as u32, ATLAS_PAD.1 as u32);
+    let mut layout = AtlasLayout::default();
+    let mut pages = Vec::<(u32, u32, Vec<(u32, u32, RgbaImage)>)>::new();
+    let (mut x, mut y, mut shelf) = (0, 0, 0);
+
+    for (name, image) in sprites {
+        let (width, height) = (image.width() + pad_x * 2, image.height() + pad_y * 2);
+        if width > PAGE_SIZE || height > PAGE_SIZE {
+            return Err(format!("{name} is larger than a {PAGE_SIZE}x{PAGE_SIZE} page"));
+        }
+
+        if x + width > PAGE_SIZE {
+            (x, y, shelf) = (0, y + shelf, 0);
+        }
+
+        if pages.is_empty() || y + height > PAGE_SIZE {
+            pages.push((0, 0, Vec::new()));
+            (x, y, shelf) = (0, 0, 0);
+        }
+
+        let page = pages.len() - 1;
+        let mode = paddings.get(&name).copied().unwrap_or_default();
+        let (page_width, page_height, placed) = pages.last_mut().unwrap();
+        layout.sprites.insert(name, AtlasLayoutSprite {
+            page,
+            rect: [x + pad_x, y + pad_y, image.width(), image.height()],
+        });
+        placed.push((x, y, pad_sprite(&image, (pad_x, pad_y), mode)));
+
+        x += width;
+        shelf = shelf.max(height);
+        *page_width = (*page_width).max(x);
+        *page_height = (*page_height).max(y + height);
+    }
+
+    let mut images = Vec::with_capacity(pages.len());
+    for (index, (width, height, placed)) in pages.into_iter().enumerate() {
+        let mut page = RgbaImage::new(width, height);
+        for (x, y, image) in placed {
+            imageops::replace(&mut page, &image, x as i64, y as i64);
+        }
+
+        layout.pages.push(AtlasLayoutPage {
+            file: format!("pages/page-{index}.png"),
+            size: [width, height],
+        });
+        images.push(page);
+    }
+
+    animations(&animation_files, &mut layout)?;
+    Ok((layout, images))
+}
+
+fn layout_source(layout: &AtlasLayout) -> Result<String, String> {
+    ron::ser::to_string_pretty(layout, Default::default()).map_err(|e| e.to_string())
+}
+
+pub fn write_atlas(layout: &AtlasLayout, pages: &[RgbaImage]) -> Result<(), String> {
+    let dir = Path::new(ATLAS);
+    let pages_dir = dir.join("pages");
+    if pages_dir.exists() {
+        fs::remove_dir_all(&pages_dir).map_err(|e| format!("{}: {e}", pages_dir.display()))?;
+    }
+    fs::create_dir_all(&pages_dir).map_err(|e| format!("{}: {e}", pages_dir.display()))?;
+
+    for (page, image) in layout.pages.iter().zip(pages) {
+        let path = dir.join(&page.file);
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png).map_err(|e| format!("{}: {e}", path.display()))?;
+        fs::write(&path, bytes).map_err(|e| format!("{}: {e}", path.display()))?;
+    }
+
+    let path = dir.join("atlas.ron");
+    fs::write(&path, layout_source(layout)? + "\n").map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// What [`atlas_watch_sys`] last saw of [`SPRITES`].
+#[cfg(feature = "dev")]
+#[derive(Default)]
+pub struct AtlasWatch {
+    next: f64,
+    stamps: Option<Vec<(PathBuf, Option<SystemTime>)>>,
+}
+
+#[cfg(feature = "dev")]
+impl AtlasWatch {
+    /// Seconds between looks, as walking the whole directory every frame would be wasteful.
+    pub const INTERVAL: f64 = 1.;
+
+    /// Every sprite, animation and the padding file, with when each was last modified.
+    fn stamps() -> Result<Vec<(PathBuf, Option<SystemTime>)>, String> {
+        let mut files = Vec::new();
+        collect(Path::new(SPRITES), &mut files)?;
+        files.push(PADDING.into());
+
+        Ok(files.into_iter()
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+                (path, modified)
+            })
+            .collect())
+    }
+}
+
+/// Repacks the atlas once a file under [`SPRITES`] is added, removed or changed. Writing it makes the asset server
+/// reload the layout, which [`atlas_reload_sys`](crate::atlas_reload_sys) then picks up.
+#[cfg(feature = "dev")]
+pub fn atlas_watch_sys(time: Res<Time>, mut watch: Local<AtlasWatch>) {
+    let now = time.elapsed_seconds_f64();
+    if now < watch.next { return };
+    watch.next = now + AtlasWatch::INTERVAL;
+
+    let stamps = match AtlasWatch::stamps() {
+        Ok(stamps) => stamps,
+        Err(e) => {
+            warn!("Couldn't watch sprites: {e}");
+            return;
+        },
+    };
+
+    // The first look only takes note; the committed atlas is kept up to date by `cix-pack --check`.
+    if watch.stamps.replace(stamps.clone()).map_or(true, |old| old == stamps) { return };
+
+    match pack_atlas().and_then(|(layout, pages)| write_atlas(&layout, &pages).map(|()| layout)) {
+        Ok(layout) => info!("Repacked {} sprites into {} pages", layout.sprites.len(), layout.pages.len()),
+        Err(e) => warn!("Couldn't repack the atlas: {e}"),
+    }
+}
+
+/// Compares pixels rather than bytes, so a different PNG encoder doesn't count as out of date.
+pub fn compare_atlas(layout: &AtlasLayout, pages: &[RgbaImage]) -> Result<(), String> {
+    let dir = Path::new(ATLAS);
+    let stale = || format!("{ATLAS} is out of date; run `cargo run --no-default-features --bin cix-pack`");
+
+    let path = dir.join("atlas.ron");
+    let source = fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let old = ron::from_str::<AtlasLayout>(&source).map_err(|e| format!("{}: {e}", path.display()))?;
+    if old != *layout {
+        return Err(stale());
+    }
+
+    for (page, image) in layout.pages.iter().zip(pages) {
+        let path = dir.join(&page.file);
+        let old = image::open(&path).map_err(|e| format!("{}: {e}", path.display()))?.into_rgba8();
+        if old != *image {
+            return Err(stale());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const GREEN: Rgba<u8> = Rgba([0, 255, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 128]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const CLEAR: Rgba<u8> = Rgba([0; 4]);
+
+    /// ```text
+    /// R G
+    /// B W
+    /// ```
+    fn quad() -> RgbaImage {
+        RgbaImage::from_fn(2, 2, |x, y| match (x, y) {
+            (0, 0) => RED,
+            (1, 0) => GREEN,
+            (0, 1) => BLUE,
+            _ => WHITE,
+        })
+    }
+
+    #[test]
+    fn transparent_leaves_border_clear() {
+        let padded = pad_sprite(&quad(), (2, 1), AtlasPadding::Transparent);
+        assert_eq!(padded.dimensions(), (6, 4));
+
+        for (x, y, &pixel) in padded.enumerate_pixels() {
+            let expected = match (x, y) {
+                (2, 1) => RED,
+                (3, 1) => GREEN,
+                (2, 2) => BLUE,
+                (3, 2) => WHITE,
+                _ => CLEAR,
+            };
+            assert_eq!(pixel, expected, "at ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn extrude_repeats_nearest_edge() {
+        let padded = pad_sprite(&quad(), (2, 1), AtlasPadding::Extrude);
+        assert_eq!(padded.dimensions(), (6, 4));
+
+        for (x, y, &pixel) in padded.enumerate_pixels() {
+            let expected = match (x < 3, y < 2) {
+                (true, true) => RED,
+                (false, true) => GREEN,
+                (true, false) => BLUE,
+                (false, false) => WHITE,
+            };
+            assert_eq!(pixel, expected, "at ({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn extrude_keeps_translucent_edges() {
+        let image = RgbaImage::from_pixel(1, 3, BLUE);
+        let padded = pad_sprite(&image, (1, 1), AtlasPadding::Extrude);
+
+        assert_eq!(padded.dimensions(), (3, 5));
+        assert!(padded.pixels().all(|&pixel| pixel == BLUE));
+    }
+
+    #[test]
+    fn zero_pad_is_identity() {
+        for mode in [AtlasPadding::Transparent, AtlasPadding::Extrude] {
+            assert_eq!(pad_sprite(&quad(), (0, 0), mode), quad());
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use bevy::{
+    prelude::*,
+    app::AppExit,
+};
+use leafwing_input_manager::{
+    prelude::*,
+    systems::update_action_state,
+};
+
+use crate::{
+    GameStates, EndStates, CixStates, FixedSet,
+    GameRng,
+    Cix, CixAction, CixActState, CixAttack, cix_attack_input_sys,
+    CixInputFrame, SimScript, SimStep, sim_take_control_sys,
+    SaveSlot,
+    WorldStartLevel, WorldStartSpawn,
+};
+
+use std::{
+    fmt, fs,
+    path::PathBuf,
+};
+
+/// Records Cix's input to a [`CixReplay`] file, or plays one back in place of the keyboard and mouse.
+pub struct ReplayPlugin {
+    pub mode: ReplayMode,
+}
+
+pub enum ReplayMode {
+    /// Records every step from Cix's first spawn on, dead ones included, saving to this path whenever Cix dies, the
+    /// game ends, or the app exits.
+    Record(PathBuf),
+    Replay(CixReplay),
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        match self.mode {
+            ReplayMode::Record(ref path) => { app
+                .insert_resource(ReplayRecorder {
+                    path: path.clone(),
+                    replay: default(),
+                })
+
+                .add_system(replay_record_start_sys.in_schedule(OnEnter(GameStates::Gameplay)))
+                .add_system(replay_record_sys
+                    .in_base_set(FixedSet::Update)
+                    .after(cix_attack_input_sys)
+                    .run_if(in_state(GameStates::Gameplay))
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                )
+                .add_system(replay_save_sys.in_schedule(OnEnter(CixStates::Dead)))
+                .add_system(replay_save_sys.in_schedule(OnEnter(EndStates::Yes)))
+                .add_system(replay_save_sys
+                    .in_base_set(CoreSet::Last)
+                    .run_if(on_event::<AppExit>())
+                );
+            },
+            ReplayMode::Replay(ref replay) => { app
+                .insert_resource(WorldStartLevel(replay.level.clone()))
+                .insert_resource(WorldStartSpawn(replay.spawn))
+                .insert_resource(GameRng::new(replay.seed))
+                .insert_resource(ReplayPlayer::new(replay.clone()))
+
+                .add_system(replay_skip_menu_sys.in_schedule(OnEnter(GameStates::MainMenu)))
+                .add_system(sim_take_control_sys.in_schedule(OnEnter(CixStates::Alive)))
+                .add_system(replay_play_sys
+                    .in_base_set(FixedSet::First)
+                    .after(update_action_state::<CixAction>)
+                    .run_if(in_state(GameStates::Gameplay))
+                    .in_schedule(CoreSchedule::FixedUpdate)
+                );
+
+                // Retrying is read through the global input map, which would let the keyboard and gamepad play along.
+                app.world.remove_resource::<InputMap<CixAction>>();
+            },
+        }
+    }
+}
+
+/// One step of recorded input.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct ReplayFrame {
+    pub input: CixInputFrame,
+    /// The aim written to [`CixAttack::at`], relative to Cix.
+    pub aim: Vec2,
+    /// [`CixAction::Retry`], read while Cix is dead.
+    pub retry: bool,
+}
+
+/// A run-length encoded recording of Cix's input, one frame per fixed step from the step Cix first spawns in. Saved as
+/// text:
+///
+/// ```text
+/// cix-replay 3
+/// level 4beeb010-c640-11ed-97c1-772602c34051
+/// spawn 640 -1184
+/// seed 202136301
+/// 40 1 0 0 0 0 0 0
+/// 8 1 0 1 0 0 0 0
+/// ```
+///
+/// `spawn` is only there for runs continued from a saved checkpoint. Every line after the header holds a frame count,
+/// the move axis, jump, attack and retry as `0` or `1`, and the aim. Version 2 replays lack `spawn`; version 1 replays
+/// only held the steps Cix was alive for, so they can't be played back and are rejected.
+#[derive(Clone, Default, Debug)]
+pub struct CixReplay {
+    pub level: String,
+    /// The [`WorldStartSpawn`] the run started from.
+    pub spawn: Option<Vec2>,
+    pub seed: u64,
+    pub runs: Vec<(u32, ReplayFrame)>,
+}
+
+impl CixReplay {
+    pub const HEADER: &'static str = "cix-replay";
+    pub const VERSION: u32 = 3;
+    /// The oldest version that still plays back the same.
+    pub const MIN_VERSION: u32 = 2;
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut lines = source.lines().enumerate().map(|(index, line)| (index + 1, line.trim())).filter(|(_, line)| !line.is_empty());
+        let version = lines.next()
+            .and_then(|(_, line)| line.strip_prefix(Self::HEADER))
+            .and_then(|version| version.trim().parse::<u32>().ok())
+            .ok_or_else(|| format!("not a replay; expected `{} {}` first", Self::HEADER, Self::VERSION))?;
+        if !(Self::MIN_VERSION..=Self::VERSION).contains(&version) {
+            return Err(format!("replay version {version} isn't supported; expected {} to {}", Self::MIN_VERSION, Self::VERSION));
+        }
+
+        let mut replay = Self::default();
+        for (line_num, line) in lines {
+            let mut words = line.split_whitespace();
+            let head = words.next().unwrap();
+
+            match head {
+                "level" => replay.level = words.next().ok_or_else(|| format!("line {line_num}: missing level iid"))?.into(),
+                "spawn" => {
+                    let mut number = || {
+                        let word = words.next().ok_or_else(|| format!("line {line_num}: missing spawn position"))?;
+                        word.parse::<f32>().map_err(|e| format!("line {line_num}: `{word}`: {e}"))
+                    };
+
+                    replay.spawn = Some(Vec2::new(number()?, number()?));
+                },
+                "seed" => {
+                    let word = words.next().ok_or_else(|| format!("line {line_num}: missing seed"))?;
+                    replay.seed = word.parse().map_err(|e| format!("line {line_num}: `{word}`: {e}"))?;
+                },
+                _ => {
+                    let count = head.parse::<u32>().map_err(|e| format!("line {line_num}: `{head}`: {e}"))?;
+                    let mut number = || {
+                        let word = words.next().ok_or_else(|| format!("line {line_num}: missing argument"))?;
+                        word.parse::<f32>().map_err(|e| format!("line {line_num}: `{word}`: {e}"))
+                    };
+
+                    let movement = Vec2::new(number()?, number()?);
+                    let (jump, attack) = (number()? != 0., number()? != 0.);
+                    let retry = number()? != 0.;
+                    let aim = Vec2::new(number()?, number()?);
+
+                    replay.runs.push((count, ReplayFrame {
+                        input: CixInputFrame { movement, jump, attack, },
+                        aim,
+                        retry,
+                    }));
+                },
+            }
+        }
+
+        if replay.level.is_empty() {
+            return Err("replay has no level".into());
+        }
+
+        Ok(replay)
+    }
+
+    pub fn push(&mut self, frame: ReplayFrame) {
+        match self.runs.last_mut() {
+            Some((count, last)) if *last == frame => *count += 1,
+            _ => self.runs.push((1, frame)),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> u32 {
+        self.runs.iter().map(|&(count, _)| count).sum()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.runs.iter().all(|&(count, _)| count == 0)
+    }
+
+    /// Converts this recording into a script `cix-sim` can run, so bug reports double as regression fixtures. Sims end
+    /// once Cix dies, so retries and the frames after the first death don't matter to them.
+    pub fn to_script(&self) -> SimScript {
+        SimScript {
+            level: Some(self.level.clone()),
+            spawn: self.spawn,
+            limit: self.len(),
+            seed: Some(self.seed),
+            steps: self.runs.iter().map(|&(frames, run)| SimStep {
+                frames,
+                input: run.input,
+                aim: Some(run.aim),
+            }).collect(),
+        }
+    }
+}
+
+impl fmt::Display for CixReplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} {}", Self::HEADER, Self::VERSION)?;
+        writeln!(f, "level {}", self.level)?;
+        if let Some(spawn) = self.spawn {
+            writeln!(f, "spawn {} {}", spawn.x, spawn.y)?;
+        }
+
+        writeln!(f, "seed {}", self.seed)?;
+        for &(count, ReplayFrame { input, aim, retry }) in &self.runs {
+            writeln!(
+                f, "{count} {} {} {} {} {} {} {}",
+                input.movement.x, input.movement.y,
+                input.jump as u8, input.attack as u8, retry as u8,
+                aim.x, aim.y,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub path: PathBuf,
+    pub replay: CixReplay,
+}
+
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub replay: CixReplay,
+    /// Frames played so far.
+    pub frame: u32,
+    /// The index into [`CixReplay::runs`] of the next frame, and how far into that run it is, so playing back doesn't
+    /// walk the runs from the start every step.
+    pub run: usize,
+    pub offset: u32,
+}
+
+impl ReplayPlayer {
+    #[inline]
+    pub fn new(replay: CixReplay) -> Self {
+        Self {
+            replay,
+            frame: 0,
+            run: 0,
+            offset: 0,
+        }
+    }
+
+    /// Whether every recorded frame has been played.
+    #[inline]
+    pub fn finished(&self) -> bool {
+        self.run >= self.replay.runs.len()
+    }
+
+    /// The next recorded frame, or `None` once the replay is over.
+    pub fn next(&mut self) -> Option<ReplayFrame> {
+        self.frame += 1;
+        while let Some(&(count, frame)) = self.replay.runs.get(self.run) {
+            self.offset += 1;
+            if self.offset >= count {
+                self.run += 1;
+                self.offset = 0;
+            }
+
+            // Empty runs hold no frames.
+            if count > 0 {
+                return Some(frame);
+            }
+        }
+
+        None
+    }
+}
+
+/// Runs before the level spawns and takes the [`WorldStartSpawn`], so continued runs are recorded from their checkpoint.
+pub fn replay_record_start_sys(
+    mut recorder: ResMut<ReplayRecorder>,
+    level: Res<WorldStartLevel>, spawn: Res<WorldStartSpawn>, rng: Res<GameRng>,
+) {
+    recorder.replay = CixReplay {
+        level: level.0.clone(),
+        spawn: **spawn,
+        seed: rng.seed,
+        runs: Vec::new(),
+    };
+}
+
+/// Cix's own input while it's alive, plus [`CixAction::Retry`] from the global action state on every step, so the
+/// respawn after a retry happens on the same step when played back. Steps before Cix first spawns aren't recorded, as
+/// how many pass depends on how long the level takes to load.
+pub fn replay_record_sys(
+    mut recorder: ResMut<ReplayRecorder>,
+    global_input: Res<CixActState>,
+    cix: Query<(&CixActState, &CixAttack), With<Cix>>,
+) {
+    let cix = cix.get_single().ok();
+    if recorder.replay.is_empty() && cix.is_none() { return };
+
+    let (input, aim) = match cix {
+        Some((input, attack)) => (CixInputFrame {
+            movement: input.axis_pair(CixAction::Move).map(|axis| axis.xy()).unwrap_or_default(),
+            jump: input.pressed(CixAction::Jump),
+            attack: input.pressed(CixAction::Attack),
+        }, attack.at),
+        None => default(),
+    };
+
+    recorder.replay.push(ReplayFrame {
+        input,
+        aim,
+        retry: global_input.pressed(CixAction::Retry),
+    });
+}
+
+pub fn replay_save_sys(recorder: Res<ReplayRecorder>) {
+    if recorder.replay.level.is_empty() { return };
+    if let Err(e) = fs::write(&recorder.path, recorder.replay.to_string()) {
+        error!("Couldn't save replay to {}: {e}", recorder.path.display());
+    }
+}
+
+/// Goes straight into the replay's level, which [`ReplayPlugin`] already set as the [`WorldStartLevel`], without a save
+/// slot to write the played back progress to.
+pub fn replay_skip_menu_sys(mut slot: ResMut<SaveSlot>, mut state: ResMut<NextState<GameStates>>) {
+    **slot = None;
+    state.set(GameStates::Gameplay);
+}
+
+/// Mirrors [`replay_record_sys`], starting with the step Cix first spawns in.
+pub fn replay_play_sys(
+    mut player: ResMut<ReplayPlayer>,
+    mut global_input: ResMut<CixActState>,
+    mut cix: Query<(&mut CixActState, &mut CixAttack), With<Cix>>,
+) {
+    let cix = cix.get_single_mut().ok();
+    if player.frame == 0 && cix.is_none() { return };
+
+    let frame = player.next();
+    if frame.is_some() && player.finished() {
+        info!("Replay finished after {} frames", player.frame);
+    }
+
+    let frame = frame.unwrap_or_default();
+    if let Some((mut input, mut attack)) = cix {
+        frame.input.apply(&mut input);
+        attack.at = frame.aim;
+    }
+
+    if frame.retry {
+        global_input.press(CixAction::Retry);
+    } else {
+        global_input.release(CixAction::Retry);
+    }
+}
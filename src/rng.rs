@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use rand::{
+    prelude::*,
+    rngs::StdRng,
+};
+
+use std::ops::{
+    Deref, DerefMut,
+};
+
+/// The source of all gameplay randomness. Two runs seeded alike with the same inputs play out identically.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl GameRng {
+    pub const SEED: u64 = 0x0c1c_5eed;
+
+    #[inline]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for GameRng {
+    #[inline]
+    fn default() -> Self {
+        Self::new(Self::SEED)
+    }
+}
+
+impl Deref for GameRng {
+    type Target = StdRng;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.rng
+    }
+}
+
+impl DerefMut for GameRng {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rng
+    }
+}
@@ -0,0 +1,318 @@
+use bevy::{
+    prelude::*,
+    app::AppExit,
+};
+use bevy_ecs_ldtk::prelude::*;
+use serde::{
+    Serialize, Deserialize,
+    de::DeserializeOwned,
+};
+
+use crate::{
+    GameStates,
+    Settings, GraphicsSettings, BindingSettings,
+    CixSpawnPos,
+    WorldStartLevel, WorldStartSpawn, WorldInit,
+    GameTime,
+};
+
+use std::{
+    fs,
+    collections::BTreeSet,
+    io::ErrorKind,
+    path::{
+        Path, PathBuf,
+    },
+    time::SystemTime,
+};
+
+/// Saves progress to the current [`SaveSlot`] on entering a level, activating a checkpoint, and exiting the app.
+/// [`SaveContinueEvent`] loads it back.
+pub struct SavePlugin;
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        let progress = LevelProgress::load().unwrap_or_else(|e| {
+            warn!("Couldn't load level progress: {e}");
+            default()
+        });
+
+        app
+            .insert_resource(progress)
+            .init_resource::<SaveSlot>()
+            .init_resource::<SaveOverrides>()
+            .add_event::<SaveEvent>()
+            .add_event::<SaveContinueEvent>()
+
+            .add_system(save_continue_sys)
+            .add_system(save_auto_sys
+                .run_if(in_state(GameStates::Gameplay))
+                .run_if(resource_exists::<WorldInit>())
+                .run_if(resource_changed::<CixSpawnPos>())
+            )
+            .add_system(save_reach_sys.run_if(in_state(GameStates::Gameplay)))
+            .add_system(save_overrides_sys)
+            .add_system(save_write_sys
+                .in_base_set(CoreSet::Last)
+                .run_if(in_state(GameStates::Gameplay))
+            );
+    }
+}
+
+/// The slot progress is saved to, or `None` to save nothing, e.g. while playing back a replay.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct SaveSlot(pub Option<u32>);
+
+/// The [`SettingsOverrides`] of the slot being played, written back along with the rest of its progress. Graphics and
+/// bindings changed while playing the slot are recorded here by [`save_overrides_sys`].
+#[derive(Resource, Deref, DerefMut, Clone, Default)]
+pub struct SaveOverrides(pub SettingsOverrides);
+
+/// Writes the current progress to [`SaveSlot`] at the end of the frame.
+#[derive(Copy, Clone)]
+pub struct SaveEvent;
+
+/// Loads the given slot and goes straight into its level, skipping [`GameStates::Prelude`].
+#[derive(Copy, Clone)]
+pub struct SaveContinueEvent(pub u32);
+
+/// Progress in one slot, saved as RON next to the [`Settings`] file.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    /// Iid of the level Cix is in.
+    pub level: String,
+    /// Where Cix spawns in that level, i.e. the last activated checkpoint.
+    pub spawn: Option<[f32; 2]>,
+    /// Seconds of gameplay so far.
+    pub elapsed: f64,
+    /// Iids of the collectibles picked up. There aren't any yet, but the set is saved anyway so the format doesn't
+    /// change once there are.
+    pub collected: BTreeSet<String>,
+    pub overrides: SettingsOverrides,
+}
+
+/// Settings a slot applies on top of the player's own [`Settings`] when continued.
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsOverrides {
+    pub graphics: Option<GraphicsSettings>,
+    pub bindings: Option<BindingSettings>,
+}
+
+impl SettingsOverrides {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.graphics.is_none() && self.bindings.is_none()
+    }
+
+    pub fn apply(&self, settings: &mut Settings) {
+        if let Some(ref graphics) = self.graphics {
+            settings.graphics = graphics.clone();
+        }
+
+        if let Some(ref bindings) = self.bindings {
+            settings.bindings = bindings.clone();
+            settings.bindings.fill_defaults();
+        }
+    }
+}
+
+impl SaveData {
+    pub const SLOTS: u32 = 3;
+
+    /// `saves/slot-<slot>.ron` in [`Settings::dir`].
+    pub fn path(slot: u32) -> Option<PathBuf> {
+        Settings::dir().map(|dir| dir.join("saves").join(format!("slot-{slot}.ron")))
+    }
+
+    /// Reads the save in `slot`, or `None` if the slot is empty.
+    pub fn load(slot: u32) -> Result<Option<Self>, String> {
+        match Self::path(slot) {
+            Some(path) => read_ron(&path),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save(&self, slot: u32) -> Result<(), String> {
+        let Some(path) = Self::path(slot) else { return Err("no config directory to save progress in".into()) };
+        write_ron(&path, self)
+    }
+
+    #[inline]
+    pub fn modified(slot: u32) -> Option<SystemTime> {
+        fs::metadata(Self::path(slot)?).and_then(|meta| meta.modified()).ok()
+    }
+
+    /// The slot saved to most recently, if any.
+    pub fn latest() -> Option<u32> {
+        (0..Self::SLOTS)
+            .filter_map(|slot| Some((slot, Self::modified(slot)?)))
+            .max_by_key(|&(_, modified)| modified)
+            .map(|(slot, _)| slot)
+    }
+
+    /// The first empty slot, or the one saved to least recently if none are.
+    pub fn free_slot() -> u32 {
+        (0..Self::SLOTS).min_by_key(|&slot| Self::modified(slot)).unwrap_or(0)
+    }
+}
+
+/// Levels entered in any slot, which the level select unlocks. Kept apart from the slots, so a new game doesn't lock
+/// them again.
+#[derive(Resource, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelProgress {
+    pub reached: BTreeSet<String>,
+}
+
+impl LevelProgress {
+    pub const FILE: &'static str = "progress.ron";
+
+    #[inline]
+    pub fn path() -> Option<PathBuf> {
+        Settings::dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    pub fn load() -> Result<Self, String> {
+        match Self::path() {
+            Some(path) => read_ron(&path).map(Option::unwrap_or_default),
+            None => Ok(default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = Self::path() else { return Err("no config directory to save progress in".into()) };
+        write_ron(&path, self)
+    }
+}
+
+/// Reads a RON file, or `None` if there isn't one.
+pub fn read_ron<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, String> {
+    match fs::read_to_string(path) {
+        Ok(source) => ron::from_str(&source).map(Some).map_err(|e| format!("{}: {e}", path.display())),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("{}: {e}", path.display())),
+    }
+}
+
+/// Writes to a temporary file first and renames it over the old file, so a crash mid-write never leaves a
+/// half-written file behind.
+pub fn write_ron<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let source = ron::ser::to_string_pretty(value, default()).map_err(|e| e.to_string())?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+
+    let temp = path.with_extension("ron.tmp");
+    fs::write(&temp, source).map_err(|e| format!("{}: {e}", temp.display()))?;
+    fs::rename(&temp, path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+pub fn save_continue_sys(
+    mut events: EventReader<SaveContinueEvent>,
+    mut slot: ResMut<SaveSlot>, mut overrides: ResMut<SaveOverrides>,
+    mut settings: ResMut<Settings>,
+    mut level: ResMut<WorldStartLevel>, mut spawn: ResMut<WorldStartSpawn>,
+    mut time: ResMut<GameTime>,
+    mut state: ResMut<NextState<GameStates>>,
+) {
+    let Some(&SaveContinueEvent(from)) = events.iter().last() else { return };
+    let data = match SaveData::load(from) {
+        Ok(Some(data)) => data,
+        Ok(None) => {
+            warn!("Save slot {from} is empty");
+            return;
+        },
+        Err(e) => {
+            warn!("Couldn't load save: {e}");
+            return;
+        },
+    };
+
+    **slot = Some(from);
+    **level = data.level;
+    **spawn = data.spawn.map(Vec2::from_array);
+    time.ticks = (data.elapsed / GameTime::STEP).round() as u64;
+
+    // Only touch the settings if there's anything to override, so they aren't needlessly saved again.
+    if !data.overrides.is_empty() {
+        data.overrides.apply(&mut settings);
+    }
+
+    **overrides = data.overrides;
+    state.set(GameStates::Gameplay);
+}
+
+/// Saves whenever [`CixSpawnPos`] moves, i.e. on entering a level or activating a checkpoint.
+pub fn save_auto_sys(mut events: EventWriter<SaveEvent>) {
+    events.send(SaveEvent);
+}
+
+pub fn save_reach_sys(mut progress: ResMut<LevelProgress>, level: Query<&LevelSet, Changed<LevelSet>>) {
+    let mut reached = false;
+    for level in &level {
+        for iid in &level.iids {
+            if !progress.reached.contains(iid) {
+                progress.reached.insert(iid.clone());
+                reached = true;
+            }
+        }
+    }
+
+    if reached && let Err(e) = progress.save() {
+        warn!("Couldn't save level progress: {e}");
+    }
+}
+
+/// Records the graphics and bindings the player changes while playing a slot as that slot's overrides, and saves them
+/// straight away. Changes made anywhere else, e.g. in the main menu, are the player's own.
+pub fn save_overrides_sys(
+    state: Res<State<GameStates>>, slot: Res<SaveSlot>,
+    settings: Res<Settings>, mut overrides: ResMut<SaveOverrides>,
+    mut last: Local<Option<(GraphicsSettings, BindingSettings)>>,
+    mut events: EventWriter<SaveEvent>,
+) {
+    if !settings.is_changed() { return };
+    let Some((graphics, bindings)) = last.replace((settings.graphics.clone(), settings.bindings.clone())) else { return };
+    if state.0 != GameStates::Gameplay || slot.is_none() { return };
+
+    let mut changed = false;
+    if settings.graphics != graphics {
+        overrides.graphics = Some(settings.graphics.clone());
+        changed = true;
+    }
+
+    if settings.bindings != bindings {
+        overrides.bindings = Some(settings.bindings.clone());
+        changed = true;
+    }
+
+    if changed {
+        events.send(SaveEvent);
+    }
+}
+
+pub fn save_write_sys(
+    mut events: EventReader<SaveEvent>, mut exit: EventReader<AppExit>,
+    slot: Res<SaveSlot>, overrides: Res<SaveOverrides>,
+    cix_pos: Res<CixSpawnPos>, time: Res<GameTime>,
+    level: Query<&LevelSet>,
+) {
+    if events.is_empty() && exit.is_empty() { return };
+    events.clear();
+    exit.clear();
+
+    let Some(slot) = **slot else { return };
+    let Some(level) = level.get_single().ok().and_then(|level| level.iids.iter().next()) else { return };
+    let data = SaveData {
+        level: level.clone(),
+        spawn: Some(cix_pos.to_array()),
+        elapsed: time.elapsed_seconds_f64(),
+        collected: default(),
+        overrides: (**overrides).clone(),
+    };
+
+    if let Err(e) = data.save(slot) {
+        warn!("Couldn't save progress: {e}");
+    }
+}
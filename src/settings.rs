@@ -0,0 +1,325 @@
+use bevy::{
+    prelude::*,
+    utils::Instant,
+    window::{
+        PrimaryWindow,
+        PresentMode, WindowMode, WindowResolution,
+    },
+};
+use leafwing_input_manager::prelude::*;
+use serde::{
+    Serialize, Deserialize,
+};
+
+use crate::{
+    CameraAction,
+    Cix, CixAction,
+    Locale,
+    write_ron,
+};
+
+use std::{
+    env, fs, thread,
+    io::ErrorKind,
+    path::PathBuf,
+    time::Duration,
+};
+
+/// Applies and saves [`Settings`]. The binary inserts [`Settings::load`]'s result before adding the window plugin, so
+/// the window opens with the right size straight away.
+pub struct SettingsPlugin;
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Settings>()
+
+            .add_systems((
+                settings_apply_window_sys,
+                settings_apply_bindings_sys,
+                settings_save_sys,
+            ).distributive_run_if(resource_changed::<Settings>()))
+            .add_system(settings_frame_cap_sys.in_base_set(CoreSet::Last));
+    }
+}
+
+/// Player settings, saved as RON in [`Settings::path`]. Missing fields fall back to their defaults, so older files
+/// keep working as new settings are added.
+#[derive(Resource, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub window: WindowSettings,
+    pub graphics: GraphicsSettings,
+    pub interface: InterfaceSettings,
+    pub bindings: BindingSettings,
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    pub width: f32,
+    pub height: f32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    /// Frames per second to cap to, or `None` to run uncapped.
+    pub frame_cap: Option<f64>,
+}
+
+impl Default for WindowSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            width: 800.,
+            height: 500.,
+            fullscreen: false,
+            vsync: false,
+            frame_cap: None,
+        }
+    }
+}
+
+impl WindowSettings {
+    #[inline]
+    pub fn resolution(&self) -> WindowResolution {
+        WindowResolution::new(self.width, self.height)
+    }
+
+    #[inline]
+    pub fn mode(&self) -> WindowMode {
+        if self.fullscreen { WindowMode::BorderlessFullscreen } else { WindowMode::Windowed }
+    }
+
+    #[inline]
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync { PresentMode::AutoVsync } else { PresentMode::AutoNoVsync }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    pub bloom: bool,
+    pub bloom_intensity: f32,
+}
+
+impl Default for GraphicsSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            bloom: true,
+            bloom_intensity: 0.4,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InterfaceSettings {
+    /// Shows the [`SpeedrunTimer`](crate::SpeedrunTimer) and its splits while playing.
+    pub speedrun_timer: bool,
+    /// Id of the [`Locale`](crate::Locale) to show text in, i.e. its file name without `.locale.ron`.
+    pub locale: String,
+}
+
+impl Default for InterfaceSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            speedrun_timer: false,
+            locale: Locale::DEFAULT.into(),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BindingSettings {
+    pub cix: InputMap<CixAction>,
+    pub camera: InputMap<CameraAction>,
+}
+
+/// Keyboard and mouse, keyboard alone with the arrow keys aiming, and a gamepad all work out of the box.
+impl Default for BindingSettings {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            cix: InputMap::default()
+                .insert(VirtualDPad {
+                    up: KeyCode::W.into(),
+                    down: KeyCode::S.into(),
+                    left: KeyCode::A.into(),
+                    right: KeyCode::D.into(),
+                }, CixAction::Move)
+                .insert(DualAxis::left_stick(), CixAction::Move)
+                .insert(VirtualDPad::dpad(), CixAction::Move)
+                .insert(KeyCode::Space, CixAction::Jump)
+                .insert(GamepadButtonType::South, CixAction::Jump)
+                .insert(MouseButton::Left, CixAction::Attack)
+                .insert(KeyCode::RShift, CixAction::Attack)
+                .insert(GamepadButtonType::RightTrigger2, CixAction::Attack)
+                .insert(VirtualDPad::arrow_keys(), CixAction::Aim)
+                .insert(DualAxis::right_stick(), CixAction::Aim)
+                .insert(KeyCode::R, CixAction::Retry)
+                .insert(GamepadButtonType::Select, CixAction::Retry)
+                .insert(KeyCode::E, CixAction::Interact)
+                .insert(GamepadButtonType::North, CixAction::Interact)
+                .build(),
+            camera: InputMap::default()
+                .insert(KeyCode::F12, CameraAction::ToggleBloom)
+                .insert(KeyCode::F3, CameraAction::ToggleDebug)
+                .build(),
+        }
+    }
+}
+
+impl BindingSettings {
+    /// Gives every action without a single binding its default ones, so actions added since the settings were saved
+    /// are bound. Rebinding only ever replaces inputs, so an action can't have been left unbound on purpose.
+    pub fn fill_defaults(&mut self) {
+        let defaults = Self::default();
+        fill_input_map(&mut self.cix, &defaults.cix);
+        fill_input_map(&mut self.camera, &defaults.camera);
+    }
+}
+
+fn fill_input_map<A: Actionlike>(input_map: &mut InputMap<A>, defaults: &InputMap<A>) {
+    for action in A::variants() {
+        if !input_map.get(action.clone()).is_empty() { continue };
+        for input in defaults.get(action.clone()).iter() {
+            input_map.insert(input.clone(), action.clone());
+        }
+    }
+}
+
+impl Settings {
+    pub const FILE: &'static str = "settings.ron";
+
+    /// `cix/` in the platform's config directory, or `None` if it can't be told.
+    pub fn dir() -> Option<PathBuf> {
+        let dir = if cfg!(target_os = "windows") {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            env::var_os("XDG_CONFIG_HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        };
+
+        dir.map(|dir| dir.join("cix"))
+    }
+
+    #[inline]
+    pub fn path() -> Option<PathBuf> {
+        Self::dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    /// Where a settings file that failed to parse is moved, so saving the defaults over it doesn't lose it.
+    #[inline]
+    pub fn backup_path() -> Option<PathBuf> {
+        Self::dir().map(|dir| dir.join(format!("{}.bak", Self::FILE)))
+    }
+
+    /// Reads the settings file, falling back to the defaults if there isn't one yet. A file that doesn't parse is moved
+    /// to [`Self::backup_path`] before the error is returned.
+    pub fn load() -> Result<Self, String> {
+        let Some(path) = Self::path() else { return Ok(default()) };
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(default()),
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        };
+
+        match ron::from_str::<Self>(&source) {
+            Ok(mut settings) => {
+                settings.bindings.fill_defaults();
+                Ok(settings)
+            },
+            Err(e) => {
+                let mut msg = format!("{}: {e}", path.display());
+                if let Some(backup) = Self::backup_path() {
+                    match fs::rename(&path, &backup) {
+                        Ok(()) => msg += &format!("; kept as {}", backup.display()),
+                        Err(e) => msg += &format!("; couldn't keep it as {}: {e}", backup.display()),
+                    }
+                }
+
+                Err(msg)
+            },
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = Self::path() else { return Err("no config directory to save settings in".into()) };
+        write_ron(&path, self)
+    }
+}
+
+/// Only touches the window when [`WindowSettings`] itself changed, so changing anything else doesn't undo the player
+/// resizing the window by hand.
+pub fn settings_apply_window_sys(
+    settings: Res<Settings>,
+    mut window: Query<&mut Window, With<PrimaryWindow>>,
+    mut applied: Local<Option<WindowSettings>>,
+) {
+    let Ok(mut window) = window.get_single_mut() else { return };
+    let window_settings = &settings.window;
+    if applied.as_ref() == Some(window_settings) { return };
+
+    if applied.as_ref().map(|applied| (applied.width, applied.height)) != Some((window_settings.width, window_settings.height)) {
+        window.resolution.set(window_settings.width, window_settings.height);
+    }
+
+    window.mode = window_settings.mode();
+    window.present_mode = window_settings.present_mode();
+    *applied = Some(window_settings.clone());
+}
+
+pub fn settings_apply_bindings_sys(
+    settings: Res<Settings>,
+    cix_input_map: Option<ResMut<InputMap<CixAction>>>,
+    mut cix: Query<&mut InputMap<CixAction>, With<Cix>>,
+    mut camera: Query<&mut InputMap<CameraAction>, With<Camera>>,
+) {
+    // Replays take the global input map away, so the player can't join in.
+    if let Some(mut cix_input_map) = cix_input_map && *cix_input_map != settings.bindings.cix {
+        *cix_input_map = settings.bindings.cix.clone();
+    }
+
+    for mut input_map in &mut cix {
+        if *input_map != settings.bindings.cix {
+            *input_map = settings.bindings.cix.clone();
+        }
+    }
+
+    for mut input_map in &mut camera {
+        if *input_map != settings.bindings.camera {
+            *input_map = settings.bindings.camera.clone();
+        }
+    }
+}
+
+/// The settings are written back whenever they change, except when first inserted. A file that failed to load was
+/// already moved aside by [`Settings::load`], so writing the defaults doesn't lose it.
+pub fn settings_save_sys(settings: Res<Settings>) {
+    if settings.is_added() { return };
+    if let Err(e) = settings.save() {
+        warn!("Couldn't save settings: {e}");
+    }
+}
+
+/// Sleeps off whatever is left of the frame when [`WindowSettings::frame_cap`] is set.
+pub fn settings_frame_cap_sys(settings: Res<Settings>, mut last: Local<Option<Instant>>) {
+    if
+        let Some(cap) = settings.window.frame_cap && cap > 0. &&
+        let Some(last) = *last
+    {
+        let frame = Duration::from_secs_f64(1. / cap);
+        let elapsed = last.elapsed();
+        if elapsed < frame {
+            thread::sleep(frame - elapsed);
+        }
+    }
+
+    *last = Some(Instant::now());
+}
@@ -0,0 +1,332 @@
+use bevy::{
+    prelude::*,
+    app::PluginGroupBuilder,
+    asset::AssetPlugin,
+    hierarchy::HierarchyPlugin,
+    input::InputPlugin,
+    time::{
+        TimeSystem, TimeUpdateStrategy,
+    },
+    utils::Instant,
+    transform::TransformPlugin,
+};
+use bevy_ecs_ldtk::prelude::*;
+use bevy_embedded_assets::EmbeddedAssetPlugin;
+use leafwing_input_manager::{
+    prelude::*,
+    axislike::DualAxisData,
+    systems::update_action_state,
+};
+
+use crate::{
+    GameStates, EndStates, CixStates, FixedSet,
+    GameRng,
+    Cix, CixAction, CixActState, CixAttack,
+    LdtkWorld, WorldStartLevel, WorldStartSpawn,
+};
+
+use std::fmt;
+
+/// Everything the gameplay plugins need to run without a window or a renderer.
+pub struct HeadlessPlugins;
+impl PluginGroup for HeadlessPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        MinimalPlugins.build()
+            .add(TransformPlugin)
+            .add(HierarchyPlugin)
+            .add(InputPlugin)
+            .add(EmbeddedAssetPlugin)
+            .add(AssetPlugin::default())
+            .add(ImagePlugin::default_linear())
+            .add(HeadlessSpritePlugin)
+    }
+}
+
+/// Registers [`TextureAtlas`] assets; `SpritePlugin` can't be added without the render app.
+pub struct HeadlessSpritePlugin;
+impl Plugin for HeadlessSpritePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<TextureAtlas>();
+    }
+}
+
+/// Drives Cix with a [`SimScript`], advancing exactly one fixed step per frame and recording the [`SimOutcome`].
+pub struct SimPlugin {
+    pub script: SimScript,
+}
+
+impl Plugin for SimPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(ref level) = self.script.level {
+            app.insert_resource(WorldStartLevel(level.clone()));
+        }
+
+        if let Some(spawn) = self.script.spawn {
+            app.insert_resource(WorldStartSpawn(Some(spawn)));
+        }
+
+        if let Some(seed) = self.script.seed {
+            app.insert_resource(GameRng::new(seed));
+        }
+
+        app
+            .insert_resource(TimeUpdateStrategy::ManualInstant(Instant::now()))
+            .insert_resource(self.script.clone())
+            .init_resource::<SimState>()
+
+            .add_system(sim_time_sys
+                .in_base_set(CoreSet::First)
+                .before(TimeSystem)
+            )
+            .add_system(sim_skip_menu_sys.in_schedule(OnEnter(GameStates::MainMenu)))
+            .add_system(sim_take_control_sys.in_schedule(OnEnter(CixStates::Alive)))
+            .add_system(sim_input_sys
+                .in_base_set(FixedSet::First)
+                .after(update_action_state::<CixAction>)
+                .run_if(in_state(CixStates::Alive))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(sim_outcome_sys.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+/// A single frame's worth of Cix input.
+#[derive(Copy, Clone, PartialEq, Default, Debug)]
+pub struct CixInputFrame {
+    pub movement: Vec2,
+    pub jump: bool,
+    pub attack: bool,
+}
+
+impl CixInputFrame {
+    pub fn apply(self, input: &mut CixActState) {
+        input.action_data_mut(CixAction::Move).axis_pair = Some(DualAxisData::from_xy(self.movement));
+        for (action, pressed) in [
+            (CixAction::Move, self.movement != Vec2::ZERO),
+            (CixAction::Jump, self.jump),
+            (CixAction::Attack, self.attack),
+        ] {
+            if pressed {
+                input.press(action);
+            } else {
+                input.release(action);
+            }
+        }
+    }
+}
+
+/// A scripted playthrough, parsed from a plain-text file:
+///
+/// ```text
+/// # Comments start with a hash.
+/// level 46500d20-c640-11ed-8b5e-75415eccb36d
+/// limit 1800
+/// aim 1 0
+/// 120 right
+/// 20 right jump
+/// 30 attack
+/// ```
+///
+/// `level` picks the starting level iid, `spawn` where in it Cix first spawns instead of its `cix` entity, `limit`
+/// caps the frames simulated after Cix first spawns, `seed` seeds [`GameRng`], and `aim` sets the attack direction
+/// relative to Cix. Every other line holds a frame count followed by any of `left`, `right`, `up`, `down`, `jump` and
+/// `attack`; an empty set idles.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct SimScript {
+    pub level: Option<String>,
+    pub spawn: Option<Vec2>,
+    pub limit: u32,
+    pub seed: Option<u64>,
+    pub steps: Vec<SimStep>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct SimStep {
+    pub frames: u32,
+    pub input: CixInputFrame,
+    pub aim: Option<Vec2>,
+}
+
+impl SimScript {
+    pub const DEFAULT_LIMIT: u32 = 60 * 60 * 5;
+    /// Frames allowed on top of [`Self::limit`] for loading and spawning, after which the sim times out even if Cix
+    /// never spawned, e.g. because the level has no usable `cix` entity.
+    pub const LOAD_LIMIT: u32 = 60 * 60;
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut script = Self {
+            limit: Self::DEFAULT_LIMIT,
+            ..default()
+        };
+
+        let mut aim = None;
+        for (index, line) in source.lines().enumerate() {
+            let line_num = index + 1;
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() { continue };
+
+            let mut words = line.split_whitespace();
+            let head = words.next().unwrap();
+
+            let number = |word: Option<&str>| word
+                .ok_or_else(|| format!("line {line_num}: missing argument"))
+                .and_then(|word| word.parse::<f32>().map_err(|e| format!("line {line_num}: `{word}`: {e}")));
+
+            match head {
+                "level" => script.level = Some(words.next().ok_or_else(|| format!("line {line_num}: missing level iid"))?.into()),
+                "spawn" => script.spawn = Some(Vec2::new(number(words.next())?, number(words.next())?)),
+                "limit" => script.limit = number(words.next())? as u32,
+                "seed" => {
+                    let word = words.next().ok_or_else(|| format!("line {line_num}: missing seed"))?;
+                    script.seed = Some(word.parse().map_err(|e| format!("line {line_num}: `{word}`: {e}"))?);
+                },
+                "aim" => aim = Some(Vec2::new(number(words.next())?, number(words.next())?).normalize_or_zero()),
+                _ => {
+                    let frames = head.parse::<u32>().map_err(|e| format!("line {line_num}: `{head}`: {e}"))?;
+                    let mut input = CixInputFrame::default();
+                    for word in words {
+                        match word {
+                            "left" => input.movement.x -= 1.,
+                            "right" => input.movement.x += 1.,
+                            "up" => input.movement.y += 1.,
+                            "down" => input.movement.y -= 1.,
+                            "jump" => input.jump = true,
+                            "attack" => input.attack = true,
+                            _ => return Err(format!("line {line_num}: unknown input `{word}`")),
+                        }
+                    }
+
+                    script.steps.push(SimStep { frames, input, aim, });
+                },
+            }
+        }
+
+        Ok(script)
+    }
+
+    pub fn frame(&self, mut frame: u32) -> Option<SimStep> {
+        for &step in &self.steps {
+            if frame < step.frames {
+                return Some(step);
+            }
+
+            frame -= step.frames;
+        }
+
+        None
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum SimOutcome {
+    /// Cix touched the flower at the end of the world.
+    Flower,
+    /// Cix went through a gate into the level with this iid.
+    Gate(String),
+    Died,
+    TimedOut,
+    /// The script's starting level isn't in the world.
+    UnknownLevel(String),
+}
+
+impl SimOutcome {
+    #[inline]
+    pub fn completed(&self) -> bool {
+        matches!(self, Self::Flower | Self::Gate(..))
+    }
+}
+
+impl fmt::Display for SimOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Flower => write!(f, "reached the flower"),
+            Self::Gate(iid) => write!(f, "went through the gate to {iid}"),
+            Self::Died => write!(f, "died"),
+            Self::TimedOut => write!(f, "timed out"),
+            Self::UnknownLevel(iid) => write!(f, "has no level {iid}"),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SimState {
+    /// Frames since the app was set up, loading included.
+    pub updates: u32,
+    /// Frames since Cix first spawned.
+    pub frame: Option<u32>,
+    pub outcome: Option<SimOutcome>,
+}
+
+pub fn sim_time_sys(mut strategy: ResMut<TimeUpdateStrategy>, fixed_time: Res<FixedTime>) {
+    if let TimeUpdateStrategy::ManualInstant(ref mut instant) = *strategy {
+        *instant += fixed_time.period;
+    }
+}
+
+pub fn sim_skip_menu_sys(
+    mut state: ResMut<SimState>, mut next_state: ResMut<NextState<GameStates>>,
+    start_level: Res<WorldStartLevel>,
+    world: Res<LdtkWorld>, ldtk_assets: Res<Assets<LdtkAsset>>,
+) {
+    let exists = ldtk_assets.get(&world.handle)
+        .map(|ldtk| ldtk.iter_levels().any(|level| level.iid == **start_level))
+        .unwrap_or(false);
+
+    if exists {
+        next_state.set(GameStates::Gameplay);
+    } else {
+        state.outcome = Some(SimOutcome::UnknownLevel(start_level.0.clone()));
+    }
+}
+
+/// Removes Cix's input map as soon as it spawns, so nothing but the script or replay presses its actions.
+pub fn sim_take_control_sys(mut commands: Commands, cix: Query<Entity, (With<Cix>, With<InputMap<CixAction>>)>) {
+    for e in &cix {
+        commands.entity(e).remove::<InputMap<CixAction>>();
+    }
+}
+
+pub fn sim_input_sys(
+    script: Res<SimScript>, mut state: ResMut<SimState>,
+    mut cix: Query<(&mut CixActState, &mut CixAttack), With<Cix>>,
+) {
+    let Ok((mut input, mut attack)) = cix.get_single_mut() else { return };
+
+    let frame = state.frame.map(|frame| frame + 1).unwrap_or(0);
+    state.frame = Some(frame);
+
+    let step = script.frame(frame);
+    step.map(|step| step.input).unwrap_or_default().apply(&mut input);
+    if let Some(aim) = step.and_then(|step| step.aim) {
+        attack.at = aim;
+    }
+}
+
+pub fn sim_outcome_sys(
+    script: Res<SimScript>, mut state: ResMut<SimState>,
+    end_state: Res<State<EndStates>>, cix_state: Res<State<CixStates>>,
+    start_level: Res<WorldStartLevel>,
+    level: Query<&LevelSet>,
+) {
+    if state.outcome.is_some() { return };
+    state.updates += 1;
+
+    state.outcome = if end_state.0 != EndStates::No {
+        Some(SimOutcome::Flower)
+    } else if cix_state.0 == CixStates::Dead {
+        Some(SimOutcome::Died)
+    } else if
+        let Ok(level) = level.get_single() &&
+        !level.iids.contains(&**start_level) &&
+        let Some(iid) = level.iids.iter().next()
+    {
+        Some(SimOutcome::Gate(iid.clone()))
+    } else if
+        state.frame.map(|frame| frame >= script.limit).unwrap_or(false) ||
+        state.updates >= script.limit.saturating_add(SimScript::LOAD_LIMIT)
+    {
+        Some(SimOutcome::TimedOut)
+    } else {
+        None
+    };
+}
@@ -0,0 +1,253 @@
+use bevy::{
+    prelude::*,
+    window::PrimaryWindow,
+};
+use bevy_ecs_ldtk::prelude::*;
+use serde::{
+    Serialize, Deserialize,
+};
+
+use crate::{
+    GameStates, EndStates, PauseStates,
+    Fonts, LdtkWorld,
+    Settings,
+    WorldStart, WorldStartLevel, WorldStartSpawn,
+    read_ron, write_ron,
+};
+
+use std::{
+    fmt::Write,
+    path::PathBuf,
+};
+
+/// Times runs from a fresh start at the world's first level to the [`Flower`](crate::Flower), splitting on every level
+/// change, and keeps the best run in [`SpeedrunBest`]. Continuing a save or starting elsewhere isn't timed, since that
+/// run is only part of one.
+pub struct SpeedrunPlugin;
+impl Plugin for SpeedrunPlugin {
+    fn build(&self, app: &mut App) {
+        let best = SpeedrunBest::load().unwrap_or_else(|e| {
+            warn!("Couldn't load best splits: {e}");
+            default()
+        });
+
+        app
+            .insert_resource(best)
+            .init_resource::<SpeedrunTimer>()
+
+            .add_systems((
+                speedrun_start_sys,
+                speedrun_hud_spawn_sys,
+            ).in_schedule(OnEnter(GameStates::Gameplay)))
+            .add_systems((
+                speedrun_tick_sys,
+                speedrun_split_sys.after(speedrun_tick_sys),
+                speedrun_hud_sys.after(speedrun_split_sys),
+            ).in_set(OnUpdate(GameStates::Gameplay)))
+            .add_system(speedrun_finish_sys.in_schedule(OnEnter(EndStates::Yes)))
+            .add_system(speedrun_hud_exit_sys.in_schedule(OnExit(GameStates::Gameplay)));
+    }
+}
+
+/// The time a level was left at, counted from the start of the run.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SpeedrunSplit {
+    pub level: String,
+    pub time: f64,
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct SpeedrunTimer {
+    pub current: Option<String>,
+    pub elapsed: f64,
+    pub splits: Vec<SpeedrunSplit>,
+    pub running: bool,
+}
+
+/// The splits of the fastest finished run, saved as RON in [`Settings::dir`]. Every timed run starts at the world's
+/// first level, so there's only ever one to compare against.
+#[derive(Resource, Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpeedrunBest {
+    pub run: Vec<SpeedrunSplit>,
+}
+
+impl SpeedrunBest {
+    pub const FILE: &'static str = "splits.ron";
+
+    #[inline]
+    pub fn path() -> Option<PathBuf> {
+        Settings::dir().map(|dir| dir.join(Self::FILE))
+    }
+
+    pub fn load() -> Result<Self, String> {
+        match Self::path() {
+            Some(path) => read_ron(&path).map(Option::unwrap_or_default),
+            None => Ok(default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = Self::path() else { return Err("no config directory to save splits in".into()) };
+        write_ron(&path, self)
+    }
+
+    /// How far ahead (negative) or behind (positive) `split` is against the same split of the best run.
+    pub fn delta(&self, index: usize, split: &SpeedrunSplit) -> Option<f64> {
+        let best = self.run.get(index)?;
+        (best.level == split.level).then(|| split.time - best.time)
+    }
+}
+
+/// Formats seconds as `m:ss.cc`.
+pub fn format_run_time(time: f64) -> String {
+    let centis = (time.abs() * 100.).round() as u64;
+    format!("{}:{:02}.{:02}", centis / 6000, centis / 100 % 60, centis % 100)
+}
+
+/// Only starts the timer if the world starts from the first level's `cix` entity, i.e. not from a saved checkpoint.
+pub fn speedrun_start_sys(
+    mut timer: ResMut<SpeedrunTimer>,
+    level: Res<WorldStartLevel>, spawn: Res<WorldStartSpawn>,
+) {
+    *timer = SpeedrunTimer {
+        current: Some((**level).clone()),
+        running: **level == WorldStart::FIRST_LEVEL && spawn.is_none(),
+        ..default()
+    };
+}
+
+/// Only counts time while the window is focused and the game isn't paused.
+pub fn speedrun_tick_sys(
+    time: Res<Time>, pause: Res<State<PauseStates>>,
+    mut timer: ResMut<SpeedrunTimer>,
+    window: Query<&Window, With<PrimaryWindow>>,
+) {
+    if
+        timer.running && pause.0 == PauseStates::Running &&
+        window.get_single().map(|window| window.focused).unwrap_or(false)
+    {
+        timer.elapsed += time.delta_seconds_f64();
+    }
+}
+
+pub fn speedrun_split_sys(
+    mut timer: ResMut<SpeedrunTimer>,
+    level: Query<&LevelSet, Changed<LevelSet>>,
+) {
+    if !timer.running { return };
+    let Some(next) = level.get_single().ok().and_then(|level| level.iids.iter().next()) else { return };
+    if timer.current.as_ref() == Some(next) { return };
+
+    if let Some(left) = timer.current.replace(next.clone()) {
+        let time = timer.elapsed;
+        timer.splits.push(SpeedrunSplit { level: left, time, });
+    }
+}
+
+pub fn speedrun_finish_sys(mut timer: ResMut<SpeedrunTimer>, mut best: ResMut<SpeedrunBest>) {
+    if !timer.running { return };
+    timer.running = false;
+
+    if let Some(level) = timer.current.clone() {
+        let time = timer.elapsed;
+        timer.splits.push(SpeedrunSplit { level, time, });
+    }
+
+    let faster = best.run.last()
+        .map(|last| timer.elapsed < last.time)
+        .unwrap_or(true);
+
+    if faster {
+        best.run = timer.splits.clone();
+        if let Err(e) = best.save() {
+            warn!("Couldn't save best splits: {e}");
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SpeedrunHud;
+
+pub fn speedrun_hud_spawn_sys(mut commands: Commands, fonts: Res<Fonts>) {
+    let style = |color: Color| TextStyle {
+        font: fonts.font.clone_weak(),
+        font_size: 20.,
+        color,
+    };
+
+    commands.spawn((
+        SpeedrunHud,
+        TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    right: Val::Px(16.),
+                    top: Val::Px(16.),
+                    ..default()
+                },
+                ..default()
+            },
+            visibility: Visibility::Hidden,
+            ..TextBundle::from_sections([
+                TextSection::new("", style(Color::WHITE)),
+                TextSection::new("", style(Color::rgba(1., 1., 1., 0.6))),
+                TextSection::new("", style(Color::WHITE)),
+            ])
+        },
+    ));
+}
+
+/// Shows the run time, then the latest split with its delta against the best run: green when ahead, red when behind,
+/// and white when dead even.
+pub fn speedrun_hud_sys(
+    settings: Res<Settings>,
+    timer: Res<SpeedrunTimer>, best: Res<SpeedrunBest>,
+    world: Res<LdtkWorld>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    mut hud: Query<(&mut Text, &mut Visibility), With<SpeedrunHud>>,
+) {
+    let Ok((mut text, mut visibility)) = hud.get_single_mut() else { return };
+    // Untimed runs have nothing to show.
+    let shown = settings.interface.speedrun_timer && (timer.running || !timer.splits.is_empty());
+    let target = if shown { Visibility::Inherited } else { Visibility::Hidden };
+    if *visibility != target {
+        *visibility = target;
+    }
+
+    if !shown { return };
+
+    text.sections[0].value = format_run_time(timer.elapsed);
+
+    let split = &mut text.sections[1].value;
+    split.clear();
+    let Some((index, last)) = timer.splits.iter().enumerate().last() else {
+        text.sections[2].value.clear();
+        return;
+    };
+
+    let name = ldtk_assets.get(&world.handle)
+        .and_then(|ldtk| ldtk.iter_levels().find(|level| level.iid == last.level))
+        .map(|level| level.identifier.as_str())
+        .unwrap_or(last.level.as_str());
+    let _ = write!(split, "\n{name} {}", format_run_time(last.time));
+
+    let delta = &mut text.sections[2];
+    delta.value.clear();
+    if let Some(diff) = best.delta(index, last) {
+        // Compared as shown, so a difference that rounds away isn't signed or colored.
+        let (sign, color) = match (diff * 100.).round() {
+            centis if centis > 0. => ("+", Color::rgb(1., 0.4, 0.4)),
+            centis if centis < 0. => ("-", Color::rgb(0.4, 1., 0.4)),
+            _ => ("", Color::WHITE),
+        };
+
+        let _ = write!(delta.value, " {sign}{}", format_run_time(diff));
+        delta.style.color = color;
+    }
+}
+
+pub fn speedrun_hud_exit_sys(mut commands: Commands, huds: Query<Entity, With<SpeedrunHud>>) {
+    for hud in &huds {
+        commands.entity(hud).despawn_recursive();
+    }
+}
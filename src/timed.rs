@@ -1,5 +1,26 @@
 use bevy::prelude::*;
 
+/// The gameplay clock, advanced by exactly [`GameTime::STEP`] every [`CoreSchedule::FixedUpdate`] run in
+/// [`GameStates::Gameplay`](crate::GameStates::Gameplay).
+#[derive(Resource, Copy, Clone, Default)]
+pub struct GameTime {
+    pub ticks: u64,
+}
+
+impl GameTime {
+    pub const STEP: f64 = 1. / 60.;
+
+    #[inline]
+    pub fn elapsed_seconds_f64(self) -> f64 {
+        self.ticks as f64 * Self::STEP
+    }
+
+    #[inline]
+    pub fn elapsed_seconds(self) -> f32 {
+        self.elapsed_seconds_f64() as f32
+    }
+}
+
 #[derive(Component, Copy, Clone)]
 pub struct Timed {
     pub life: f64,
@@ -45,8 +66,8 @@ impl Timed {
     }
 }
 
-pub fn timed_update_sys(time: Res<Time>, mut all: Query<&mut Timed>) {
-    let delta = time.delta_seconds_f64();
+pub fn timed_update_sys(mut all: Query<&mut Timed>) {
+    let delta = GameTime::STEP;
     for mut timed in &mut all {
         let d = delta * timed.scale;
         if timed.backwards {
@@ -64,3 +85,7 @@ pub fn timed_post_update_sys(mut commands: Commands, all: Query<(Entity, &Timed)
         }
     }
 }
+
+pub fn game_time_update_sys(mut time: ResMut<GameTime>) {
+    time.ticks += 1;
+}
@@ -0,0 +1,204 @@
+use bevy::prelude::*;
+
+use crate::{
+    ext::*,
+    Timed,
+};
+
+/// Named curves for [`Timed::fin`] and [`Tween`]s, so effects don't spell the polynomials out.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// `f²`; starts slow.
+    QuadIn,
+    /// `1 - (1 - f)²`; ends slow.
+    QuadOut,
+    /// `f³`.
+    CubicIn,
+    /// `1 - (1 - f)³`.
+    CubicOut,
+    /// `3f² - 2f³`; starts and ends slow.
+    SmoothStep,
+}
+
+impl Easing {
+    #[inline]
+    pub fn ease(self, f: f32) -> f32 {
+        match self {
+            Self::Linear => f,
+            Self::QuadIn => f * f,
+            Self::QuadOut => 1. - (f - 1.) * (f - 1.),
+            Self::CubicIn => f * f * f,
+            Self::CubicOut => (f - 1.) * (f - 1.) * (f - 1.) + 1.,
+            Self::SmoothStep => f * f * (3. - 2. * f),
+        }
+    }
+}
+
+impl Timed {
+    /// [`Self::fin`] through `easing`.
+    #[inline]
+    pub fn ease(self, easing: Easing) -> f32 {
+        easing.ease(self.fin())
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Keyframe<T> {
+    /// Where in the [`Timed`] this key is reached, from 0 to 1.
+    pub at: f32,
+    pub value: T,
+    /// How the value moves from the previous key to this one.
+    pub easing: Easing,
+}
+
+/// Values over the course of a [`Timed`], held at the first key before it and at the last key after it.
+#[derive(Clone, Debug)]
+pub struct Keyframes<T> {
+    pub keys: Vec<Keyframe<T>>,
+}
+
+impl<T: LerpExt + Copy> Keyframes<T> {
+    /// Starts at `value`.
+    #[inline]
+    pub fn start(value: T) -> Self {
+        Self {
+            keys: vec![Keyframe { at: 0., value, easing: Easing::Linear, }],
+        }
+    }
+
+    /// Moves from `from` to `to` over the whole [`Timed`].
+    #[inline]
+    pub fn between(from: T, to: T, easing: Easing) -> Self {
+        Self::start(from).then(1., to, easing)
+    }
+
+    /// Adds a key at `at`, which must come after the previous one.
+    #[inline]
+    pub fn then(mut self, at: f32, value: T, easing: Easing) -> Self {
+        self.keys.push(Keyframe { at, value, easing, });
+        self
+    }
+
+    pub fn sample(&self, f: f32) -> Option<T> {
+        let first = self.keys.first()?;
+        if f <= first.at {
+            return Some(first.value);
+        }
+
+        for pair in self.keys.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            if f < to.at {
+                let local = (f - from.at) / (to.at - from.at);
+                return Some(from.value.lerp(to.value, to.easing.ease(local)));
+            }
+        }
+
+        self.keys.last().map(|key| key.value)
+    }
+}
+
+/// One property of an entity a [`Tween`] drives.
+#[derive(Clone, Debug)]
+pub enum TweenTrack {
+    /// Only `x` and `y`; `z` is the draw order and stays put.
+    Translation(Keyframes<Vec2>),
+    Rotation(Keyframes<Quat>),
+    Scale(Keyframes<Vec3>),
+    /// Of the entity's [`TextureAtlasSprite`].
+    Color(Keyframes<Color>),
+    /// The [`TextureAtlasSprite::custom_size`].
+    Size(Keyframes<Vec2>),
+}
+
+/// Drives the entity's [`Transform`] and [`TextureAtlasSprite`] along its tracks over its [`Timed`], and sends a
+/// [`TweenDoneEvent`] once it ends.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Tween {
+    pub tracks: Vec<TweenTrack>,
+    pub done: bool,
+}
+
+/// Sent once a [`Tween`]'s [`Timed`] ends. Timed entities despawn in the same step, so this is the last chance to react.
+#[derive(Copy, Clone, Debug)]
+pub struct TweenDoneEvent {
+    pub entity: Entity,
+}
+
+impl Tween {
+    #[inline]
+    pub fn new() -> Self {
+        default()
+    }
+
+    #[inline]
+    pub fn translation(self, keys: Keyframes<Vec2>) -> Self {
+        self.with(TweenTrack::Translation(keys))
+    }
+
+    #[inline]
+    pub fn rotation(self, keys: Keyframes<Quat>) -> Self {
+        self.with(TweenTrack::Rotation(keys))
+    }
+
+    #[inline]
+    pub fn scale(self, keys: Keyframes<Vec3>) -> Self {
+        self.with(TweenTrack::Scale(keys))
+    }
+
+    #[inline]
+    pub fn color(self, keys: Keyframes<Color>) -> Self {
+        self.with(TweenTrack::Color(keys))
+    }
+
+    #[inline]
+    pub fn size(self, keys: Keyframes<Vec2>) -> Self {
+        self.with(TweenTrack::Size(keys))
+    }
+
+    #[inline]
+    pub fn with(mut self, track: TweenTrack) -> Self {
+        self.tracks.push(track);
+        self
+    }
+
+    /// Writes every track's value at `f` into `trns` and `sprite`, skipping the ones that aren't there.
+    pub fn apply(&self, f: f32, mut trns: Option<&mut Transform>, mut sprite: Option<&mut TextureAtlasSprite>) {
+        for track in &self.tracks {
+            match (track, trns.as_deref_mut(), sprite.as_deref_mut()) {
+                (TweenTrack::Translation(keys), Some(trns), _) => if let Some(pos) = keys.sample(f) {
+                    trns.translation = pos.extend(trns.translation.z);
+                },
+                (TweenTrack::Rotation(keys), Some(trns), _) => if let Some(rot) = keys.sample(f) {
+                    trns.rotation = rot;
+                },
+                (TweenTrack::Scale(keys), Some(trns), _) => if let Some(scl) = keys.sample(f) {
+                    trns.scale = scl;
+                },
+                (TweenTrack::Color(keys), _, Some(sprite)) => if let Some(color) = keys.sample(f) {
+                    sprite.color = color;
+                },
+                (TweenTrack::Size(keys), _, Some(sprite)) => if let Some(size) = keys.sample(f) {
+                    sprite.custom_size = Some(size);
+                },
+                _ => {},
+            }
+        }
+    }
+}
+
+pub fn tween_sys(
+    mut tweens: Query<(Entity, &mut Tween, &Timed, Option<&mut Transform>, Option<&mut TextureAtlasSprite>)>,
+    mut events: EventWriter<TweenDoneEvent>,
+) {
+    for (entity, mut tween, &timed, trns, sprite) in &mut tweens {
+        if tween.done { continue };
+
+        tween.apply(timed.fin(), trns.map(Mut::into_inner), sprite.map(Mut::into_inner));
+        if timed.ended() {
+            tween.done = true;
+            events.send(TweenDoneEvent { entity });
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use crate::{
+    MESSAGE,
+    GameStates,
+    Lang,
+    Typewriter, TypewriterDoneEvent,
+};
+
+#[derive(Component)]
+pub struct GameEnd;
+
+#[derive(Component)]
+pub struct EndText;
+
+impl EndText {
+    /// How long each page but the last stays up.
+    pub const PAGE_TIME: f64 = 2.;
+    /// How long the last page stays up before going back to the main menu.
+    pub const RETURN_TIME: f64 = 6.;
+}
+
+pub fn game_end_enter_sys(mut commands: Commands, lang: Lang) {
+    // `message.txt` predates locale markup, so its blank lines still separate pages.
+    let source = match MESSAGE {
+        Some(message) => message.replace("\n\n", "[page]"),
+        None => lang.locale().ending.clone(),
+    };
+
+    commands.spawn((
+        GameEnd,
+        NodeBundle {
+            style: Style {
+                size: Size::all(Val::Percent(100.)),
+                padding: UiRect::all(Val::Px(32.)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::NONE),
+            ..default()
+        },
+    )).with_children(|builder| { builder.spawn((
+        EndText,
+        Typewriter::new(&source, TextStyle {
+            font: lang.font(),
+            font_size: 24.,
+            color: Color::WHITE,
+        }).with_waits(Some(EndText::PAGE_TIME), Some(EndText::RETURN_TIME)),
+        TextBundle {
+            style: Style {
+                size: Size::new(Val::Px(700.), Val::Percent(100.)),
+                ..default()
+            },
+            ..default()
+        },
+    )); });
+}
+
+/// Returns to the main menu once the last page is dismissed, or after [`EndText::RETURN_TIME`].
+pub fn game_end_update_sys(
+    mut events: EventReader<TypewriterDoneEvent>,
+    mut game_state: ResMut<NextState<GameStates>>,
+    text: Query<(), With<EndText>>,
+) {
+    if events.iter().any(|&TypewriterDoneEvent { entity }| text.contains(entity)) {
+        game_state.set(GameStates::MainMenu);
+    }
+}
+
+pub fn game_end_exit_sys(mut commands: Commands, ends: Query<Entity, With<GameEnd>>) {
+    for end in &ends {
+        commands.entity(end).despawn_recursive();
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::{
+    prelude::*,
+    ecs::system::SystemParam,
+};
+use iyes_progress::prelude::*;
+
+use crate::{
+    LdtkWorld, BackgroundImages, AtlasAssets, GameAtlas, Fonts,
+    Settings,
+    Locale, Locales, format_text,
+};
+
+/// Shown throughout [`GameStates::Loading`](crate::GameStates::Loading), so a slow load doesn't look like a frozen
+/// window.
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+pub struct LoadingBar;
+
+#[derive(Component)]
+pub struct LoadingPercent;
+
+#[derive(Component)]
+pub struct LoadingPending;
+
+impl LoadingScreen {
+    /// Loaded straight from the [`AssetServer`], as [`Fonts`] is one of the things being waited on.
+    pub const FONT: &'static str = "fonts/font.ttf";
+    pub const BAR_SIZE: Vec2 = Vec2::new(400., 6.);
+    pub const BAR_COLOR: Color = Color::rgb(0.4, 1.8, 3.);
+    pub const TRACK_COLOR: Color = Color::rgba(1., 1., 1., 0.1);
+}
+
+/// Every asset collection loaded during [`GameStates::Loading`](crate::GameStates::Loading), plus [`GameAtlas`], which
+/// is built out of them. Each is a resource only once it's ready.
+#[derive(SystemParam)]
+pub struct LoadingParts<'w> {
+    world: Option<Res<'w, LdtkWorld>>,
+    backgrounds: Option<Res<'w, BackgroundImages>>,
+    sprites: Option<Res<'w, AtlasAssets>>,
+    atlas: Option<Res<'w, GameAtlas>>,
+    fonts: Option<Res<'w, Fonts>>,
+    locales: Option<Res<'w, Locales>>,
+}
+
+impl<'w> LoadingParts<'w> {
+    /// The [`Locale`] key and English name of every part that isn't ready yet.
+    pub fn pending(&self) -> impl Iterator<Item = (&'static str, &'static str)> {
+        [
+            ("loading.world", "world", self.world.is_some()),
+            ("loading.backgrounds", "backgrounds", self.backgrounds.is_some()),
+            ("loading.sprites", "sprites", self.sprites.is_some()),
+            ("loading.atlas", "atlas", self.atlas.is_some()),
+            ("loading.fonts", "fonts", self.fonts.is_some()),
+            ("loading.locales", "locales", self.locales.is_some()),
+        ].into_iter().filter(|&(.., ready)| !ready).map(|(key, name, _)| (key, name))
+    }
+}
+
+pub fn loading_enter_sys(mut commands: Commands, server: Res<AssetServer>) {
+    let font = server.load(LoadingScreen::FONT);
+    commands.spawn((
+        LoadingScreen,
+        NodeBundle {
+            style: Style {
+                size: Size::all(Val::Percent(100.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                gap: Size::all(Val::Px(12.)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::BLACK),
+            ..default()
+        },
+    )).with_children(|builder| {
+        builder.spawn((
+            LoadingPercent,
+            TextBundle::from_section("0%", TextStyle {
+                font: font.clone(),
+                font_size: 32.,
+                color: Color::WHITE,
+            }),
+        ));
+
+        builder.spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Px(LoadingScreen::BAR_SIZE.x), Val::Px(LoadingScreen::BAR_SIZE.y)),
+                ..default()
+            },
+            background_color: BackgroundColor(LoadingScreen::TRACK_COLOR),
+            ..default()
+        }).with_children(|builder| { builder.spawn((
+            LoadingBar,
+            NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(0.), Val::Percent(100.)),
+                    ..default()
+                },
+                background_color: BackgroundColor(LoadingScreen::BAR_COLOR),
+                ..default()
+            },
+        )); });
+
+        builder.spawn((
+            LoadingPending,
+            TextBundle::from_section("", TextStyle {
+                font,
+                font_size: 16.,
+                color: Color::rgba(1., 1., 1., 0.6),
+            }),
+        ));
+    });
+}
+
+/// Shows what [`ProgressCounter`] has counted by now; runs after every tracked system has reported this frame. The
+/// [`Locales`] are among the parts being loaded, so the text is in English until they're in.
+pub fn loading_update_sys(
+    counter: Option<Res<ProgressCounter>>,
+    parts: LoadingParts,
+    settings: Res<Settings>, locale_assets: Res<Assets<Locale>>,
+    mut bar: Query<&mut Style, With<LoadingBar>>,
+    mut percent: Query<&mut Text, (With<LoadingPercent>, Without<LoadingPending>)>,
+    mut pending: Query<&mut Text, (With<LoadingPending>, Without<LoadingPercent>)>,
+) {
+    let Some(counter) = counter else { return };
+    let progress = counter.progress();
+    let f = if progress.total == 0 { 0. } else { progress.done as f32 / progress.total as f32 };
+
+    if let Ok(mut bar) = bar.get_single_mut() {
+        bar.size.width = Val::Percent(f * 100.);
+    }
+
+    if let Ok(mut text) = percent.get_single_mut() {
+        let value = format!("{}%", (f * 100.).floor() as u32);
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+
+    if let Ok(mut text) = pending.get_single_mut() {
+        let locale = parts.locales.as_ref().and_then(|locales| Locale::select(
+            locales.all.iter().filter_map(|handle| locale_assets.get(handle)),
+            &settings.interface.locale,
+        ));
+        let text_of = |key: &str, fallback: &'static str| locale
+            .and_then(|locale| locale.text(key))
+            .map_or_else(|| fallback.to_owned(), str::to_owned);
+
+        let names = parts.pending().map(|(key, name)| text_of(key, name)).collect::<Vec<_>>();
+        let value = if names.is_empty() {
+            String::new()
+        } else {
+            format_text(&text_of("loading.pending", "Loading {parts}..."), &[("parts", &names.join(", "))])
+        };
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+pub fn loading_exit_sys(mut commands: Commands, screens: Query<Entity, With<LoadingScreen>>) {
+    for screen in &screens {
+        commands.entity(screen).despawn_recursive();
+    }
+}
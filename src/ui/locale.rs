@@ -0,0 +1,161 @@
+use bevy::{
+    prelude::*,
+    asset::{
+        AssetLoader, LoadContext, LoadedAsset,
+    },
+    ecs::system::SystemParam,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use bevy_asset_loader::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    Fonts,
+    Settings,
+};
+
+use std::collections::HashMap;
+
+/// The game's text in one language, loaded from `locale/<id>.locale.ron`.
+#[derive(TypeUuid, Clone, Debug, Deserialize)]
+#[uuid = "6c1b8e0e-5f7a-4d2b-9c43-1e8f2a7d9b05"]
+pub struct Locale {
+    /// The file name without `.locale.ron`, filled in by [`LocaleLoader`].
+    #[serde(skip)]
+    pub id: String,
+    /// The language's own name for itself, shown in the settings.
+    pub name: String,
+    /// Asset path of a font to use instead of [`Fonts::font`], for scripts it doesn't cover.
+    #[serde(default)]
+    pub font: Option<String>,
+    /// In [`Typewriter`](crate::Typewriter) markup, as is [`Self::ending`].
+    pub prelude: String,
+    /// A `message.txt` next to the sources at build time takes its place.
+    pub ending: String,
+    /// Menu, pause, binding and loading text by key, e.g. `menu.new_game`. Values may hold `{name}` placeholders,
+    /// filled in by [`Lang::format`]. Keys missing here fall back to [`Locale::DEFAULT`]'s, so a translation can be
+    /// partial.
+    #[serde(default)]
+    pub ui: HashMap<String, String>,
+}
+
+impl Locale {
+    pub const DEFAULT: &'static str = "en";
+
+    /// The locale with the given id out of `all`, falling back to [`Self::DEFAULT`] and then to any.
+    pub fn select<'a>(mut all: impl Iterator<Item = &'a Locale> + Clone, id: &str) -> Option<&'a Locale> {
+        all.clone().find(|locale| locale.id == id)
+            .or_else(|| all.clone().find(|locale| locale.id == Self::DEFAULT))
+            .or_else(|| all.next())
+    }
+
+    #[inline]
+    pub fn text(&self, key: &str) -> Option<&str> {
+        self.ui.get(key).map(String::as_str)
+    }
+}
+
+/// Replaces every `{name}` in `text` with its value in `args`.
+pub fn format_text(text: &str, args: &[(&str, &str)]) -> String {
+    let mut text = text.to_owned();
+    for &(name, value) in args {
+        text = text.replace(&format!("{{{name}}}"), value);
+    }
+
+    text
+}
+
+#[derive(Default)]
+pub struct LocaleLoader;
+impl AssetLoader for LocaleLoader {
+    fn load<'a>(&'a self, bytes: &'a [u8], load_context: &'a mut LoadContext) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let mut locale = ron::de::from_bytes::<Locale>(bytes)?;
+            locale.id = load_context.path()
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".locale.ron"))
+                .unwrap_or(Locale::DEFAULT)
+                .into();
+
+            load_context.set_default_asset(LoadedAsset::new(locale));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+/// Every `.locale.ron` file in `assets/locale`, which holds nothing else. Translations are added by dropping a file in.
+#[derive(AssetCollection, Resource)]
+pub struct Locales {
+    #[asset(path = "locale", collection(typed))]
+    pub all: Vec<Handle<Locale>>,
+}
+
+/// The font of the selected [`Locale`], if it has one, kept loaded while selected.
+#[derive(Resource, Default)]
+pub struct LocaleFont {
+    pub path: Option<String>,
+    pub handle: Option<Handle<Font>>,
+}
+
+/// The selected [`Locale`] and the font to show it in.
+#[derive(SystemParam)]
+pub struct Lang<'w> {
+    pub settings: Res<'w, Settings>,
+    pub locales: Res<'w, Locales>,
+    pub assets: Res<'w, Assets<Locale>>,
+    pub fonts: Res<'w, Fonts>,
+    pub font: Res<'w, LocaleFont>,
+}
+
+impl<'w> Lang<'w> {
+    /// Falls back to [`Locale::DEFAULT`] if the selected locale doesn't exist, e.g. after it was removed.
+    pub fn locale(&self) -> &Locale {
+        Locale::select(self.iter(), &self.settings.interface.locale).expect("no locales loaded")
+    }
+
+    /// The UI text under `key` in the selected locale, or [`Locale::DEFAULT`]'s, or the key itself if neither has it.
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        self.locale().text(key)
+            .or_else(|| self.iter().find(|locale| locale.id == Locale::DEFAULT).and_then(|locale| locale.text(key)))
+            .unwrap_or(key)
+    }
+
+    /// [`Self::text`] with its placeholders filled in.
+    #[inline]
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        format_text(self.text(key), args)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &Locale> + Clone {
+        self.locales.all.iter().filter_map(|handle| self.assets.get(handle))
+    }
+
+    #[inline]
+    pub fn font(&self) -> Handle<Font> {
+        self.font.handle.as_ref().unwrap_or(&self.fonts.font).clone_weak()
+    }
+}
+
+/// Loads the selected locale's font through the [`AssetServer`], and drops the previous one.
+pub fn locale_font_sys(
+    settings: Res<Settings>,
+    server: Res<AssetServer>,
+    locales: Res<Locales>, assets: Res<Assets<Locale>>,
+    mut font: ResMut<LocaleFont>,
+) {
+    let path = locales.all.iter()
+        .filter_map(|handle| assets.get(handle))
+        .find(|locale| locale.id == settings.interface.locale)
+        .and_then(|locale| locale.font.clone());
+    if font.path == path { return };
+
+    font.handle = path.as_ref().map(|path| server.load(path.as_str()));
+    font.path = path;
+}
@@ -0,0 +1,427 @@
+use bevy::{
+    prelude::*,
+    app::AppExit,
+};
+use bevy_ecs_ldtk::prelude::*;
+
+use crate::{
+    GameStates,
+    LdtkWorld,
+    Settings,
+    Lang, Locale, Locales,
+    SaveData, SaveSlot, SaveOverrides, SaveContinueEvent, LevelProgress,
+    WorldStartLevel, WorldStartSpawn,
+    GameTime, format_run_time,
+    CixBinding,
+};
+
+#[derive(Component)]
+pub struct MainMenu;
+
+#[derive(Resource, Copy, Clone, Eq, PartialEq, Default)]
+pub enum MainMenuPage {
+    #[default]
+    Main,
+    Levels,
+    Settings,
+    /// Lists the save slots, to pick one for the [`SlotPurpose`].
+    Slots,
+    /// Asks before starting over in a slot that already holds progress.
+    Overwrite(u32),
+}
+
+/// What picking a slot on [`MainMenuPage::Slots`] does.
+#[derive(Resource, Clone, Eq, PartialEq, Debug, Default)]
+pub enum SlotPurpose {
+    #[default]
+    Continue,
+    NewGame,
+}
+
+/// The index of the focused [`MenuItem`], moved by the keyboard, a gamepad, or hovering with the mouse. Shared with
+/// the [`PauseMenu`](crate::PauseMenu), which is never open at the same time.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct MenuFocus(pub usize);
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum MenuButton {
+    NewGame,
+    Continue,
+    LevelSelect,
+    Settings,
+    Quit,
+    Level(String),
+    Slot(u32),
+    Overwrite(u32),
+    Fullscreen,
+    Vsync,
+    Bloom,
+    SpeedrunTimer,
+    Language,
+    Back,
+    /// The [`PauseMenu`](crate::PauseMenu)'s, carried out by [`pause_action_sys`](crate::pause_action_sys).
+    Resume,
+    Restart,
+    Controls,
+    Bind(CixBinding),
+}
+
+#[derive(Component, Clone)]
+pub struct MenuItem {
+    pub button: MenuButton,
+    pub index: usize,
+    pub enabled: bool,
+}
+
+impl MenuButton {
+    pub const COLOR: Color = Color::rgba(1., 1., 1., 0.6);
+    pub const COLOR_FOCUSED: Color = Color::WHITE;
+    pub const COLOR_PRESSED: Color = Color::rgb(0.4, 1.8, 3.);
+    pub const COLOR_DISABLED: Color = Color::rgba(1., 1., 1., 0.2);
+
+    /// Levels and slots are labelled with what they hold by [`menu_page_sys`] instead.
+    pub fn label(&self, settings: &Settings, lang: &Lang) -> String {
+        let toggle = |key: &str, value: bool| lang.format(key, &[("value", lang.text(if value { "menu.on" } else { "menu.off" }))]);
+        let slot = |key: &str, slot: u32| lang.format(key, &[("slot", &(slot + 1).to_string())]);
+        match *self {
+            Self::NewGame => lang.text("menu.new_game").into(),
+            Self::Continue => lang.text("menu.continue").into(),
+            Self::LevelSelect => lang.text("menu.level_select").into(),
+            Self::Settings => lang.text("menu.settings").into(),
+            Self::Quit => lang.text("menu.quit").into(),
+            Self::Level(ref iid) => iid.clone(),
+            Self::Slot(index) => slot("menu.slot", index),
+            Self::Overwrite(index) => lang.format("menu.overwrite", &[("save", &slot("menu.slot", index))]),
+            Self::Fullscreen => toggle("menu.fullscreen", settings.window.fullscreen),
+            Self::Vsync => toggle("menu.vsync", settings.window.vsync),
+            Self::Bloom => toggle("menu.bloom", settings.graphics.bloom),
+            Self::SpeedrunTimer => toggle("menu.speedrun_timer", settings.interface.speedrun_timer),
+            Self::Language => lang.format("menu.language", &[("value", &lang.locale().name)]),
+            Self::Back => lang.text("menu.back").into(),
+            Self::Resume => lang.text("pause.resume").into(),
+            Self::Restart => lang.text("pause.restart").into(),
+            Self::Controls => lang.text("pause.controls").into(),
+            Self::Bind(binding) => lang.text(binding.key()).into(),
+        }
+    }
+}
+
+/// Pressed by [`menu_nav_sys`] or [`menu_mouse_sys`], and carried out by [`menu_action_sys`].
+#[derive(Clone)]
+pub struct MenuActivateEvent(pub MenuButton);
+
+pub fn menu_enter_sys(mut commands: Commands, mut page: ResMut<MainMenuPage>) {
+    *page = MainMenuPage::Main;
+    commands.spawn((
+        MainMenu,
+        NodeBundle {
+            style: Style {
+                size: Size::all(Val::Percent(100.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                gap: Size::height(Val::Px(12.)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::NONE),
+            ..default()
+        },
+    ));
+}
+
+/// Fills the menu with the items of the current [`MainMenuPage`]. Changing the [`Settings`] rebuilds the page too, so
+/// the toggles show their new values; focus only resets when the page itself changes.
+pub fn menu_page_sys(
+    mut commands: Commands,
+    page: Res<MainMenuPage>, purpose: Res<SlotPurpose>, settings: Res<Settings>, progress: Res<LevelProgress>,
+    mut focus: ResMut<MenuFocus>,
+    lang: Lang,
+    world: Res<LdtkWorld>, ldtk_assets: Res<Assets<LdtkAsset>>,
+    menu: Query<Entity, With<MainMenu>>,
+) {
+    if !page.is_changed() && !settings.is_changed() && !lang.font.is_changed() { return };
+    let Ok(menu) = menu.get_single() else { return };
+
+    let ldtk = ldtk_assets.get(&world.handle);
+    let slot_label = |key: &str, slot: u32, level: &str, time: &str| lang.format(key, &[
+        ("slot", &(slot + 1).to_string()),
+        ("level", level),
+        ("time", time),
+    ]);
+
+    // "Slot 2: dreams, 1:23.45", or `None` if the slot is empty.
+    let slot_summary = |slot: u32| match SaveData::load(slot) {
+        Ok(Some(data)) => {
+            let level = ldtk.into_iter()
+                .flat_map(|ldtk| ldtk.iter_levels())
+                .find(|level| level.iid == data.level)
+                .map_or("?", |level| level.identifier.as_str());
+            Some(slot_label("menu.slot_saved", slot, level, &format_run_time(data.elapsed)))
+        },
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Couldn't load save: {e}");
+            Some(slot_label("menu.slot_unreadable", slot, "", ""))
+        },
+    };
+
+    let items = match *page {
+        MainMenuPage::Main => vec![
+            (MenuButton::NewGame, None, true),
+            (MenuButton::Continue, None, SaveData::latest().is_some()),
+            (MenuButton::LevelSelect, None, true),
+            (MenuButton::Settings, None, true),
+            (MenuButton::Quit, None, true),
+        ],
+        // Listed in world order. The first level is always open; the rest unlock once entered in any slot.
+        MainMenuPage::Levels => ldtk.into_iter()
+            .flat_map(|ldtk| ldtk.iter_levels())
+            .enumerate()
+            .map(|(i, level)| {
+                let unlocked = i == 0 || progress.reached.contains(&level.iid);
+                let name = if unlocked { level.identifier.as_str() } else { lang.text("menu.level_locked") };
+                let label = lang.format("menu.level", &[("number", &(i + 1).to_string()), ("name", name)]);
+                (MenuButton::Level(level.iid.clone()), Some(label), unlocked)
+            })
+            .chain([(MenuButton::Back, None, true)])
+            .collect(),
+        MainMenuPage::Settings => vec![
+            (MenuButton::Fullscreen, None, true),
+            (MenuButton::Vsync, None, true),
+            (MenuButton::Bloom, None, true),
+            (MenuButton::SpeedrunTimer, None, true),
+            (MenuButton::Language, None, lang.iter().count() > 1),
+            (MenuButton::Back, None, true),
+        ],
+        MainMenuPage::Slots => (0..SaveData::SLOTS)
+            .map(|slot| {
+                let summary = slot_summary(slot);
+                // Empty slots can't be continued.
+                let enabled = summary.is_some() || *purpose != SlotPurpose::Continue;
+                (MenuButton::Slot(slot), Some(summary.unwrap_or_else(|| slot_label("menu.slot_empty", slot, "", ""))), enabled)
+            })
+            .chain([(MenuButton::Back, None, true)])
+            .collect(),
+        MainMenuPage::Overwrite(slot) => vec![
+            (MenuButton::Overwrite(slot), slot_summary(slot).map(|save| lang.format("menu.overwrite", &[("save", &save)])), true),
+            (MenuButton::Back, Some(lang.text("menu.keep").into()), true),
+        ],
+    };
+
+    if page.is_changed() {
+        // Continuing starts on the slot saved to last, and anything else on `SaveData::free_slot`.
+        let preferred = match (*page, &*purpose) {
+            (MainMenuPage::Slots, SlotPurpose::Continue) => SaveData::latest().map(|slot| slot as usize),
+            (MainMenuPage::Slots, _) => Some(SaveData::free_slot() as usize),
+            // Overwriting is never the default.
+            (MainMenuPage::Overwrite(_), _) => Some(1),
+            _ => None,
+        };
+
+        **focus = preferred
+            .filter(|&index| items.get(index).map_or(false, |&(_, _, enabled)| enabled))
+            .or_else(|| items.iter().position(|&(_, _, enabled)| enabled))
+            .unwrap_or(0);
+    }
+
+    let mut menu = commands.entity(menu);
+    menu.despawn_descendants();
+    menu.with_children(|builder| {
+        for (index, (button, label, enabled)) in items.into_iter().enumerate() {
+            let label = label.unwrap_or_else(|| button.label(&settings, &lang));
+
+            builder.spawn((
+                MenuItem { button, index, enabled, },
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::new(Val::Px(16.), Val::Px(16.), Val::Px(4.), Val::Px(4.)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::NONE),
+                    ..default()
+                },
+            )).with_children(|builder| { builder.spawn(
+                TextBundle::from_section(label, TextStyle {
+                    font: lang.font(),
+                    font_size: 32.,
+                    color: MenuButton::COLOR,
+                }),
+            ); });
+        }
+    });
+}
+
+/// Up and down move the focus, skipping disabled items; Enter, Space or the gamepad's south button activate it, and
+/// Escape or the east button activate the page's [`MenuButton::Back`], if it has one.
+pub fn menu_nav_sys(
+    keys: Res<Input<KeyCode>>, gamepad_buttons: Res<Input<GamepadButton>>,
+    mut focus: ResMut<MenuFocus>,
+    items: Query<&MenuItem>,
+    mut events: EventWriter<MenuActivateEvent>,
+) {
+    let pad = |button_type: GamepadButtonType| gamepad_buttons.get_just_pressed().any(|button| button.button_type == button_type);
+
+    let mut items = items.iter().collect::<Vec<_>>();
+    if items.is_empty() { return };
+    items.sort_by_key(|item| item.index);
+
+    let dir = if keys.any_just_pressed([KeyCode::Up, KeyCode::W]) || pad(GamepadButtonType::DPadUp) {
+        -1
+    } else if keys.any_just_pressed([KeyCode::Down, KeyCode::S]) || pad(GamepadButtonType::DPadDown) {
+        1
+    } else {
+        0
+    };
+
+    if dir != 0 {
+        let len = items.len() as isize;
+        let mut index = **focus as isize;
+        for _ in 0..len {
+            index = (index + dir).rem_euclid(len);
+            if items[index as usize].enabled {
+                **focus = index as usize;
+                break;
+            }
+        }
+    }
+
+    if keys.any_just_pressed([KeyCode::Return, KeyCode::Space]) || pad(GamepadButtonType::South) {
+        if let Some(item) = items.get(**focus) && item.enabled {
+            events.send(MenuActivateEvent(item.button.clone()));
+        }
+    } else if
+        (keys.just_pressed(KeyCode::Escape) || pad(GamepadButtonType::East)) &&
+        items.iter().any(|item| item.button == MenuButton::Back)
+    {
+        events.send(MenuActivateEvent(MenuButton::Back));
+    }
+}
+
+/// Items fire on release rather than on press, so the click doesn't carry over into Cix's attack input.
+pub fn menu_mouse_sys(
+    items: Query<(Entity, &MenuItem, &Interaction), Changed<Interaction>>,
+    mut focus: ResMut<MenuFocus>,
+    mut pressed: Local<Option<Entity>>,
+    mut events: EventWriter<MenuActivateEvent>,
+) {
+    for (e, item, &interaction) in &items {
+        if !item.enabled { continue };
+        match interaction {
+            Interaction::Clicked => {
+                **focus = item.index;
+                *pressed = Some(e);
+            },
+            Interaction::Hovered => {
+                **focus = item.index;
+                if pressed.take() == Some(e) {
+                    events.send(MenuActivateEvent(item.button.clone()));
+                }
+            },
+            Interaction::None => {
+                if *pressed == Some(e) {
+                    *pressed = None;
+                }
+            },
+        }
+    }
+}
+
+pub fn menu_style_sys(
+    focus: Res<MenuFocus>,
+    items: Query<(&MenuItem, &Interaction, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (item, &interaction, children) in &items {
+        let color = if !item.enabled {
+            MenuButton::COLOR_DISABLED
+        } else if interaction == Interaction::Clicked {
+            MenuButton::COLOR_PRESSED
+        } else if item.index == **focus {
+            MenuButton::COLOR_FOCUSED
+        } else {
+            MenuButton::COLOR
+        };
+
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) && text.sections[0].style.color != color {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
+
+pub fn menu_action_sys(
+    mut events: EventReader<MenuActivateEvent>,
+    mut page: ResMut<MainMenuPage>, mut purpose: ResMut<SlotPurpose>,
+    mut settings: ResMut<Settings>,
+    locales: Res<Locales>, locale_assets: Res<Assets<Locale>>,
+    (mut slot, mut overrides): (ResMut<SaveSlot>, ResMut<SaveOverrides>),
+    mut level: ResMut<WorldStartLevel>, mut spawn: ResMut<WorldStartSpawn>,
+    mut time: ResMut<GameTime>,
+    mut state: ResMut<NextState<GameStates>>,
+    mut continue_events: EventWriter<SaveContinueEvent>,
+    mut exit: EventWriter<AppExit>,
+) {
+    for MenuActivateEvent(button) in &mut events {
+        match *button {
+            MenuButton::NewGame | MenuButton::Continue => {
+                *purpose = if *button == MenuButton::NewGame { SlotPurpose::NewGame } else { SlotPurpose::Continue };
+                *page = MainMenuPage::Slots;
+            },
+            // Picking a level goes straight into it without a slot, so practicing it never touches a save.
+            MenuButton::Level(ref iid) => {
+                **slot = None;
+                **overrides = default();
+                **level = iid.clone();
+                **spawn = None;
+                time.ticks = 0;
+                state.set(GameStates::Gameplay);
+            },
+            MenuButton::Slot(from) if *purpose == SlotPurpose::Continue => continue_events.send(SaveContinueEvent(from)),
+            MenuButton::Slot(to) if SaveData::modified(to).is_some() => *page = MainMenuPage::Overwrite(to),
+            MenuButton::Slot(to) | MenuButton::Overwrite(to) => {
+                **slot = Some(to);
+                // A fresh slot has no overrides of its own yet.
+                **overrides = default();
+                *level = default();
+                **spawn = None;
+                // Otherwise the new slot would carry on from the last run's play time.
+                time.ticks = 0;
+                state.set(GameStates::Prelude);
+            },
+            MenuButton::LevelSelect => *page = MainMenuPage::Levels,
+            MenuButton::Settings => *page = MainMenuPage::Settings,
+            MenuButton::Quit => exit.send(AppExit),
+            MenuButton::Fullscreen => settings.window.fullscreen = !settings.window.fullscreen,
+            MenuButton::Vsync => settings.window.vsync = !settings.window.vsync,
+            MenuButton::Bloom => settings.graphics.bloom = !settings.graphics.bloom,
+            MenuButton::SpeedrunTimer => settings.interface.speedrun_timer = !settings.interface.speedrun_timer,
+            // Cycles through the locales in the order they're listed in.
+            MenuButton::Language => {
+                let ids = locales.all.iter()
+                    .filter_map(|handle| locale_assets.get(handle))
+                    .map(|locale| &locale.id)
+                    .collect::<Vec<_>>();
+
+                if !ids.is_empty() {
+                    let next = ids.iter()
+                        .position(|&id| *id == settings.interface.locale)
+                        .map(|i| (i + 1) % ids.len())
+                        .unwrap_or(0);
+                    settings.interface.locale = ids[next].clone();
+                }
+            },
+            MenuButton::Back => *page = match *page {
+                MainMenuPage::Overwrite(_) => MainMenuPage::Slots,
+                _ => MainMenuPage::Main,
+            },
+            MenuButton::Resume | MenuButton::Restart | MenuButton::Controls | MenuButton::Bind(_) => {},
+        }
+    }
+}
+
+pub fn menu_exit_sys(mut commands: Commands, menus: Query<Entity, With<MainMenu>>) {
+    for menu in &menus {
+        commands.entity(menu).despawn_recursive();
+    }
+}
@@ -0,0 +1,97 @@
+use bevy::{
+    prelude::*,
+    ui::UiSystem,
+};
+use bevy_asset_loader::prelude::*;
+
+use crate::{
+    GameStates, PauseStates,
+    Fonts,
+};
+
+mod ending;
+mod loading;
+mod locale;
+mod menu;
+mod pause;
+mod prelude;
+mod typewriter;
+mod warnings;
+
+pub use ending::*;
+pub use loading::*;
+pub use locale::*;
+pub use menu::*;
+pub use pause::*;
+pub use prelude::*;
+pub use typewriter::*;
+pub use warnings::*;
+
+pub struct UiPlugin;
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_collection_to_loading_state::<_, Fonts>(GameStates::Loading)
+            .add_asset::<Locale>()
+            .init_asset_loader::<LocaleLoader>()
+            .add_collection_to_loading_state::<_, Locales>(GameStates::Loading)
+            .init_resource::<LocaleFont>()
+            .add_system(locale_font_sys.run_if(resource_exists::<Locales>()))
+
+            .add_system(loading_enter_sys.in_schedule(OnEnter(GameStates::Loading)))
+            .add_system(loading_update_sys
+                .in_base_set(CoreSet::PostUpdate)
+                .before(UiSystem::Flex)
+                .run_if(in_state(GameStates::Loading))
+            )
+            .add_system(loading_exit_sys.in_schedule(OnExit(GameStates::Loading)))
+            .add_system(asset_warnings_sys)
+
+            .add_event::<TypewriterPressEvent>()
+            .add_event::<TypewriterPageEvent>()
+            .add_event::<TypewriterDoneEvent>()
+            // Typewriters hold still behind the pause menu, like everything else in the world.
+            .add_system(typewriter_sys.run_if(in_state(PauseStates::Running)))
+
+            .init_resource::<MainMenuPage>()
+            .init_resource::<SlotPurpose>()
+            .init_resource::<MenuFocus>()
+            .add_event::<MenuActivateEvent>()
+            .add_system(menu_enter_sys.in_schedule(OnEnter(GameStates::MainMenu)))
+            .add_systems((
+                menu_page_sys.before(menu_nav_sys),
+                menu_action_sys.after(menu_nav_sys).after(menu_mouse_sys),
+            ).in_set(OnUpdate(GameStates::MainMenu)))
+            // Shared by the main menu and the pause menu, which are never open at the same time.
+            .add_systems((
+                menu_nav_sys,
+                menu_mouse_sys,
+                menu_style_sys,
+            ).distributive_run_if(in_state(GameStates::MainMenu).or_else(in_state(PauseStates::Paused))))
+            .add_system(menu_exit_sys.in_schedule(OnExit(GameStates::MainMenu)))
+
+            .add_system(prelude_enter_sys.in_schedule(OnEnter(GameStates::Prelude)))
+            .add_system(prelude_update_sys
+                .in_set(OnUpdate(GameStates::Prelude))
+                .after(typewriter_sys)
+            )
+            .add_system(prelude_exit_sys.in_schedule(OnExit(GameStates::Prelude)))
+
+            .init_resource::<PausePage>()
+            .add_system(pause_toggle_sys.in_set(OnUpdate(GameStates::Gameplay)))
+            .add_system(pause_enter_sys.in_schedule(OnEnter(PauseStates::Paused)))
+            .add_systems((
+                pause_page_sys.before(menu_nav_sys),
+                pause_action_sys.after(menu_nav_sys).after(menu_mouse_sys),
+                pause_bind_text_sys,
+            ).in_set(OnUpdate(PauseStates::Paused)))
+            .add_system(pause_exit_sys.in_schedule(OnExit(PauseStates::Paused)))
+
+            .add_system(game_end_enter_sys.in_schedule(OnEnter(GameStates::Ending)))
+            .add_system(game_end_update_sys
+                .in_set(OnUpdate(GameStates::Ending))
+                .after(typewriter_sys)
+            )
+            .add_system(game_end_exit_sys.in_schedule(OnExit(GameStates::Ending)));
+    }
+}
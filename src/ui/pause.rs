@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameStates, EndStates, PauseStates,
+    WorldRestartEvent,
+    SaveEvent,
+    Settings,
+    CixBinding, CixRebind,
+    MenuButton, MenuItem, MenuFocus, MenuActivateEvent,
+    Lang,
+};
+
+#[derive(Component)]
+pub struct PauseMenu;
+
+#[derive(Resource, Copy, Clone, Eq, PartialEq, Default)]
+pub enum PausePage {
+    #[default]
+    Main,
+    Controls,
+}
+
+impl PausePage {
+    pub fn buttons(self) -> Vec<MenuButton> {
+        match self {
+            Self::Main => vec![
+                MenuButton::Resume,
+                MenuButton::Restart,
+                MenuButton::Controls,
+                MenuButton::Quit,
+            ],
+            Self::Controls => CixBinding::ALL.iter()
+                .map(|&binding| MenuButton::Bind(binding))
+                .chain([MenuButton::Back])
+                .collect(),
+        }
+    }
+}
+
+/// Off the main page, Escape goes back to it through [`menu_nav_sys`](crate::menu_nav_sys) rather than resuming.
+pub fn pause_toggle_sys(
+    keys: Res<Input<KeyCode>>, gamepad_buttons: Res<Input<GamepadButton>>,
+    end_state: Res<State<EndStates>>, page: Res<PausePage>,
+    state: Res<State<PauseStates>>, mut next_state: ResMut<NextState<PauseStates>>,
+) {
+    let escape = keys.just_pressed(KeyCode::Escape) && (state.0 == PauseStates::Running || *page == PausePage::Main);
+    if
+        !escape &&
+        !gamepad_buttons.get_just_pressed().any(|button| button.button_type == GamepadButtonType::Start)
+    { return };
+    next_state.set(match state.0 {
+        PauseStates::Running if end_state.0 == EndStates::No => PauseStates::Paused,
+        _ => PauseStates::Running,
+    });
+}
+
+pub fn pause_enter_sys(mut commands: Commands, mut page: ResMut<PausePage>) {
+    *page = PausePage::Main;
+    commands.spawn((
+        PauseMenu,
+        NodeBundle {
+            style: Style {
+                size: Size::all(Val::Percent(100.)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.6)),
+            ..default()
+        },
+    ));
+}
+
+/// Fills the menu with the items of the current [`PausePage`] whenever it changes. They're navigated like the main
+/// menu's, by [`menu_nav_sys`](crate::menu_nav_sys) and [`menu_mouse_sys`](crate::menu_mouse_sys).
+pub fn pause_page_sys(
+    mut commands: Commands,
+    page: Res<PausePage>, mut focus: ResMut<MenuFocus>,
+    settings: Res<Settings>, lang: Lang,
+    mut menu: Query<(Entity, &mut Style), With<PauseMenu>>,
+) {
+    if !page.is_changed() { return };
+    let Ok((menu, mut style)) = menu.get_single_mut() else { return };
+
+    let (font_size, gap) = match *page {
+        PausePage::Main => (32., 16.),
+        PausePage::Controls => (20., 2.),
+    };
+
+    **focus = 0;
+    style.gap = Size::height(Val::Px(gap));
+    let mut menu = commands.entity(menu);
+    menu.despawn_descendants();
+    menu.with_children(|builder| {
+        for (index, button) in page.buttons().into_iter().enumerate() {
+            let label = button.label(&settings, &lang);
+            builder.spawn((
+                MenuItem { button, index, enabled: true, },
+                ButtonBundle {
+                    style: Style {
+                        padding: UiRect::new(Val::Px(16.), Val::Px(16.), Val::Px(4.), Val::Px(4.)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor(Color::NONE),
+                    ..default()
+                },
+            )).with_children(|builder| { builder.spawn(
+                TextBundle::from_section(label, TextStyle {
+                    font: lang.font(),
+                    font_size,
+                    color: MenuButton::COLOR,
+                }),
+            ); });
+        }
+    });
+}
+
+/// Quitting saves and goes back to [`GameStates::MainMenu`], where [`world_exit_sys`](crate::world_exit_sys) tears the
+/// world down.
+pub fn pause_action_sys(
+    mut events: EventReader<MenuActivateEvent>,
+    mut state: ResMut<NextState<PauseStates>>, mut game_state: ResMut<NextState<GameStates>>,
+    mut page: ResMut<PausePage>,
+    mut rebind: ResMut<CixRebind>,
+    mut restart: EventWriter<WorldRestartEvent>,
+    mut save: EventWriter<SaveEvent>,
+) {
+    for MenuActivateEvent(button) in &mut events {
+        match *button {
+            MenuButton::Resume => state.set(PauseStates::Running),
+            MenuButton::Restart => {
+                restart.send(WorldRestartEvent);
+                state.set(PauseStates::Running);
+            },
+            MenuButton::Controls => *page = PausePage::Controls,
+            MenuButton::Quit => {
+                save.send(SaveEvent);
+                game_state.set(GameStates::MainMenu);
+            },
+            MenuButton::Bind(binding) => **rebind = Some(binding),
+            MenuButton::Back => *page = PausePage::Main,
+            _ => {},
+        }
+    }
+}
+
+/// Lists what every [`CixBinding`] is bound to, per [`BindDevice`](crate::BindDevice).
+pub fn pause_bind_text_sys(
+    settings: Res<Settings>, rebind: Res<CixRebind>, lang: Lang,
+    items: Query<(&MenuItem, &Children)>,
+    mut texts: Query<&mut Text>,
+) {
+    for (item, children) in &items {
+        let MenuButton::Bind(binding) = item.button else { continue };
+
+        let action = lang.text(binding.key());
+        let value = if **rebind == Some(binding) {
+            lang.format("bind.waiting", &[("action", action)])
+        } else {
+            let inputs = binding.describe(&settings.bindings.cix, &lang);
+            let inputs = if inputs.is_empty() { lang.text("bind.none").into() } else { inputs.join(" / ") };
+            lang.format("bind.entry", &[("action", action), ("inputs", &inputs)])
+        };
+
+        for &child in children {
+            if let Ok(mut text) = texts.get_mut(child) && text.sections[0].value != value {
+                text.sections[0].value = value.clone();
+            }
+        }
+    }
+}
+
+pub fn pause_exit_sys(
+    mut commands: Commands,
+    mut rebind: ResMut<CixRebind>,
+    menus: Query<Entity, With<PauseMenu>>,
+) {
+    **rebind = None;
+    for menu in &menus {
+        commands.entity(menu).despawn_recursive();
+    }
+}
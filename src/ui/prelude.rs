@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+
+use crate::{
+    GameStates,
+    Lang,
+    Typewriter, TypewriterDoneEvent,
+};
+
+#[derive(Component)]
+pub struct WorldPrelude;
+
+#[derive(Component)]
+pub struct PreludeText;
+
+pub fn prelude_enter_sys(mut commands: Commands, lang: Lang) {
+    commands.spawn((
+        WorldPrelude,
+        NodeBundle {
+            style: Style {
+                size: Size::all(Val::Percent(100.)),
+                padding: UiRect::all(Val::Px(32.)),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: BackgroundColor(Color::NONE),
+            ..default()
+        },
+    )).with_children(|builder| { builder.spawn((
+        PreludeText,
+        Typewriter::new(&lang.locale().prelude, TextStyle {
+            font: lang.font(),
+            font_size: 32.,
+            color: Color::WHITE,
+        }),
+        TextBundle {
+            style: Style {
+                size: Size::new(Val::Px(700.), Val::Percent(100.)),
+                ..default()
+            },
+            ..default()
+        },
+    )); });
+}
+
+/// Starts playing once the prelude is dismissed.
+pub fn prelude_update_sys(
+    mut events: EventReader<TypewriterDoneEvent>,
+    mut game_state: ResMut<NextState<GameStates>>,
+    text: Query<(), With<PreludeText>>,
+) {
+    if events.iter().any(|&TypewriterDoneEvent { entity }| text.contains(entity)) {
+        game_state.set(GameStates::Gameplay);
+    }
+}
+
+pub fn prelude_exit_sys(mut commands: Commands, preludes: Query<Entity, With<WorldPrelude>>) {
+    for prelude in &preludes {
+        commands.entity(prelude).despawn_recursive();
+    }
+}
@@ -0,0 +1,394 @@
+use bevy::prelude::*;
+
+/// Reveals text a character at a time into its entity's [`Text`], one page after another.
+///
+/// The text may contain markup:
+/// - `[page]` starts a new page.
+/// - `[color=#rrggbb]` ... `[/color]` colors the text in between; colors nest.
+/// - `[pause=<seconds>]` waits before going on.
+/// - `[speed=<factor>]` types faster or slower from there until the end of the page.
+/// - `[[` is a literal `[`.
+#[derive(Component, Clone, Debug)]
+pub struct Typewriter {
+    pub pages: Vec<TypewriterPage>,
+    pub style: TextStyle,
+    pub delays: TypewriterDelays,
+    /// Seconds a finished page stays up before the next one, or `None` to wait for a press.
+    pub page_wait: Option<f64>,
+    /// Like [`Self::page_wait`], but for the last page.
+    pub end_wait: Option<f64>,
+    /// Whether pressing Enter, Space or the gamepad's south button completes the page, or advances a completed one.
+    /// A [`TypewriterPressEvent`] does the same either way.
+    pub skippable: bool,
+
+    pub page: usize,
+    /// How many characters of the current page are shown.
+    pub index: usize,
+    /// How many of the current page's marks have been applied.
+    pub mark: usize,
+    pub speed: f64,
+    /// Seconds since the current page started.
+    pub time: f64,
+    /// When the next character is due, or when the page was completed, in [`Self::time`].
+    pub next: f64,
+    pub done: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TypewriterPage {
+    /// Each visible character with its color, or `None` for [`Typewriter::style`]'s.
+    pub chars: Vec<(char, Option<Color>)>,
+    /// Pauses and speed changes, each with the number of characters shown before it takes effect.
+    pub marks: Vec<(usize, TypewriterMark)>,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TypewriterMark {
+    Pause(f64),
+    Speed(f64),
+}
+
+/// Seconds to wait after revealing a character, by what kind of character it is.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct TypewriterDelays {
+    pub newline: f64,
+    pub alphanumeric: f64,
+    pub whitespace: f64,
+    pub other: f64,
+}
+
+impl Default for TypewriterDelays {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            newline: 1.,
+            alphanumeric: 0.02,
+            whitespace: 0.05,
+            other: 0.04,
+        }
+    }
+}
+
+impl TypewriterDelays {
+    #[inline]
+    pub fn of(&self, c: char) -> f64 {
+        if c == '\n' {
+            self.newline
+        } else if c.is_alphanumeric() {
+            self.alphanumeric
+        } else if c.is_whitespace() {
+            self.whitespace
+        } else {
+            self.other
+        }
+    }
+}
+
+/// Presses the given typewriter as if it were [`Typewriter::skippable`] and Enter was pressed, for typewriters driven
+/// by other inputs.
+#[derive(Copy, Clone, Debug)]
+pub struct TypewriterPressEvent {
+    pub entity: Entity,
+}
+
+/// Sent when a page is completely shown, whether typed out or skipped.
+#[derive(Copy, Clone, Debug)]
+pub struct TypewriterPageEvent {
+    pub entity: Entity,
+    pub page: usize,
+}
+
+/// Sent when the last page is dismissed.
+#[derive(Copy, Clone, Debug)]
+pub struct TypewriterDoneEvent {
+    pub entity: Entity,
+}
+
+impl Typewriter {
+    pub fn new(source: &str, style: TextStyle) -> Self {
+        Self {
+            pages: Self::parse(source),
+            style,
+            delays: default(),
+            page_wait: None,
+            end_wait: None,
+            skippable: true,
+            page: 0,
+            index: 0,
+            mark: 0,
+            speed: 1.,
+            time: 0.,
+            next: 0.,
+            done: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_delays(mut self, delays: TypewriterDelays) -> Self {
+        self.delays = delays;
+        self
+    }
+
+    #[inline]
+    pub fn with_waits(mut self, page_wait: Option<f64>, end_wait: Option<f64>) -> Self {
+        self.page_wait = page_wait;
+        self.end_wait = end_wait;
+        self
+    }
+
+    #[inline]
+    pub fn with_skippable(mut self, skippable: bool) -> Self {
+        self.skippable = skippable;
+        self
+    }
+
+    /// Splits `source` into pages and strips its markup. Malformed tags are kept as plain text.
+    pub fn parse(source: &str) -> Vec<TypewriterPage> {
+        let mut pages = vec![TypewriterPage::default()];
+        let mut colors = Vec::new();
+
+        let mut rest = source;
+        while let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+            if c == '[' {
+                if let Some(after) = rest.strip_prefix('[') {
+                    rest = after;
+                } else if let Some((tag, after)) = rest.split_once(']') && Self::parse_tag(tag, &mut pages, &mut colors) {
+                    rest = after;
+                    continue;
+                }
+            }
+
+            pages.last_mut().unwrap().chars.push((c, colors.last().copied()));
+        }
+
+        pages
+    }
+
+    fn parse_tag(tag: &str, pages: &mut Vec<TypewriterPage>, colors: &mut Vec<Color>) -> bool {
+        let (name, value) = tag.split_once('=').unwrap_or((tag, ""));
+        let page = pages.last_mut().unwrap();
+        let at = page.chars.len();
+
+        match (name, value.trim()) {
+            ("page", "") => pages.push(default()),
+            ("color", hex) => match Color::hex(hex.trim_start_matches('#')) {
+                Ok(color) => colors.push(color),
+                Err(_) => return false,
+            },
+            ("/color", "") => { colors.pop(); },
+            ("pause", secs) => match secs.parse::<f64>() {
+                Ok(secs) => page.marks.push((at, TypewriterMark::Pause(secs.max(0.)))),
+                Err(_) => return false,
+            },
+            ("speed", factor) => match factor.parse::<f64>() {
+                Ok(factor) if factor > 0. => page.marks.push((at, TypewriterMark::Speed(factor))),
+                _ => return false,
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    #[inline]
+    pub fn page_done(&self) -> bool {
+        self.pages.get(self.page).map_or(true, |page| self.index >= page.chars.len())
+    }
+
+    /// Advances time by `delta` seconds, revealing every character that came due. Returns whether any did.
+    pub fn step(&mut self, delta: f64) -> bool {
+        let Some(page) = self.pages.get(self.page) else { return false };
+        self.time += delta;
+
+        let mut revealed = false;
+        let mut paused = false;
+        loop {
+            while let Some(&(at, mark)) = page.marks.get(self.mark) && at <= self.index {
+                self.mark += 1;
+                match mark {
+                    TypewriterMark::Pause(secs) => {
+                        self.next += secs;
+                        paused = true;
+                    },
+                    TypewriterMark::Speed(factor) => self.speed = factor,
+                }
+            }
+
+            if self.index >= page.chars.len() || self.time < self.next { break };
+
+            let (c, _) = page.chars[self.index];
+            self.index += 1;
+            self.next += self.delays.of(c) / self.speed;
+            revealed = true;
+            paused = false;
+        }
+
+        // A pause at the end of the page holds it up for longer before it moves on.
+        if revealed && !paused && self.index >= page.chars.len() {
+            self.next = self.time;
+        }
+
+        revealed
+    }
+
+    /// Shows the rest of the current page at once.
+    pub fn complete(&mut self) {
+        let Some(page) = self.pages.get(self.page) else { return };
+        self.index = page.chars.len();
+        self.mark = page.marks.len();
+        self.next = self.time;
+    }
+
+    /// Moves on to the next page, or finishes if this was the last. Returns whether there was a next page.
+    pub fn advance(&mut self) -> bool {
+        if self.page + 1 >= self.pages.len() {
+            self.done = true;
+            return false;
+        }
+
+        self.page += 1;
+        self.index = 0;
+        self.mark = 0;
+        self.speed = 1.;
+        self.time = 0.;
+        self.next = 0.;
+        true
+    }
+
+    /// The shown part of the current page, one section per run of the same color.
+    pub fn sections(&self) -> Vec<TextSection> {
+        let mut sections = Vec::<TextSection>::new();
+        let Some(page) = self.pages.get(self.page) else { return sections };
+
+        for &(c, color) in &page.chars[..self.index] {
+            let color = color.unwrap_or(self.style.color);
+            match sections.last_mut() {
+                Some(section) if section.style.color == color => section.value.push(c),
+                _ => sections.push(TextSection::new(c, TextStyle {
+                    color,
+                    ..self.style.clone()
+                })),
+            }
+        }
+
+        sections
+    }
+}
+
+pub fn typewriter_sys(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>, gamepad_buttons: Res<Input<GamepadButton>>,
+    mut writers: Query<(Entity, &mut Typewriter, &mut Text)>,
+    mut press_events: EventReader<TypewriterPressEvent>,
+    mut page_events: EventWriter<TypewriterPageEvent>, mut done_events: EventWriter<TypewriterDoneEvent>,
+) {
+    let pressed =
+        keys.any_just_pressed([KeyCode::Return, KeyCode::Space]) ||
+        gamepad_buttons.get_just_pressed().any(|button| button.button_type == GamepadButtonType::South);
+
+    let presses = press_events.iter().map(|event| event.entity).collect::<Vec<_>>();
+    for (entity, mut writer, mut text) in &mut writers {
+        if writer.done { continue };
+        let pressed = (pressed && writer.skippable) || presses.contains(&entity);
+
+        if writer.page_done() {
+            writer.time += time.delta_seconds_f64();
+            let last = writer.page + 1 >= writer.pages.len();
+            let wait = if last { writer.end_wait } else { writer.page_wait };
+
+            if pressed || wait.map_or(false, |wait| writer.time - writer.next >= wait) {
+                if writer.advance() {
+                    text.sections = writer.sections();
+                } else {
+                    done_events.send(TypewriterDoneEvent { entity });
+                }
+            }
+
+            continue;
+        }
+
+        let revealed = if pressed {
+            writer.complete();
+            true
+        } else {
+            writer.step(time.delta_seconds_f64())
+        };
+
+        if revealed {
+            text.sections = writer.sections();
+            if writer.page_done() {
+                page_events.send(TypewriterPageEvent { entity, page: writer.page });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(page: &TypewriterPage) -> String {
+        page.chars.iter().map(|&(c, _)| c).collect()
+    }
+
+    fn hex(hex: &str) -> Option<Color> {
+        Some(Color::hex(hex).unwrap())
+    }
+
+    #[test]
+    fn double_bracket_is_literal() {
+        let pages = Typewriter::parse("[[page] [[[color=#ff0000]a");
+        assert_eq!(pages.len(), 1);
+        assert_eq!(text(&pages[0]), "[page] [a");
+        assert_eq!(pages[0].chars.last(), Some(&('a', hex("ff0000"))));
+    }
+
+    #[test]
+    fn malformed_tags_stay_text() {
+        for source in ["[color=#zz]", "[pause=soon]", "[speed=0]", "[speed=-1]", "[page=2]", "[shout]", "[unclosed"] {
+            let pages = Typewriter::parse(source);
+            assert_eq!(pages.len(), 1, "{source}");
+            assert_eq!(text(&pages[0]), source);
+            assert!(pages[0].marks.is_empty(), "{source}");
+            assert!(pages[0].chars.iter().all(|&(_, color)| color.is_none()), "{source}");
+        }
+    }
+
+    #[test]
+    fn colors_nest() {
+        let pages = Typewriter::parse("a[color=#ff0000]b[color=#00ff00]c[/color]d[/color]e[/color]f");
+        assert_eq!(pages[0].chars, [
+            ('a', None),
+            ('b', hex("ff0000")),
+            ('c', hex("00ff00")),
+            ('d', hex("ff0000")),
+            ('e', None),
+            ('f', None),
+        ]);
+    }
+
+    #[test]
+    fn multibyte_text() {
+        let pages = Typewriter::parse("héllo[pause=0.5]…[page]猫[color=#0000ff]🐈[/color]");
+        assert_eq!(pages.len(), 2);
+        assert_eq!(text(&pages[0]), "héllo…");
+        assert_eq!(pages[0].marks, [(5, TypewriterMark::Pause(0.5))]);
+        assert_eq!(pages[1].chars, [('猫', None), ('🐈', hex("0000ff"))]);
+    }
+
+    #[test]
+    fn trailing_pause_holds_page() {
+        let mut writer = Typewriter::new("a[pause=1][page]b", default())
+            .with_delays(TypewriterDelays {
+                newline: 0.,
+                alphanumeric: 0.,
+                whitespace: 0.,
+                other: 0.,
+            });
+
+        assert!(writer.step(0.1));
+        assert!(writer.page_done());
+        assert_eq!(writer.next, 1.);
+    }
+}
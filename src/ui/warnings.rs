@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::{
+    Fonts,
+    GameAtlas, WorldIssues,
+};
+
+/// Lists what's wrong with the loaded assets in a corner of the screen, e.g. LDtk entities that were skipped, so they're
+/// noticed without reading the log.
+#[derive(Component)]
+pub struct AssetWarnings;
+
+impl AssetWarnings {
+    pub const COLOR: Color = Color::rgba(1., 0.8, 0.2, 0.9);
+    /// Further warnings are only counted.
+    pub const MAX_LINES: usize = 8;
+}
+
+pub fn asset_warnings_sys(
+    mut commands: Commands,
+    fonts: Option<Res<Fonts>>,
+    atlas: Option<Res<GameAtlas>>, issues: Res<WorldIssues>,
+    mut warnings: Query<&mut Text, With<AssetWarnings>>,
+) {
+    let Some(fonts) = fonts else { return };
+    let changed = fonts.is_added() || issues.is_changed() || atlas.as_ref().map_or(false, |atlas| atlas.is_changed());
+    if !changed { return };
+
+    let lines = atlas.iter()
+        .flat_map(|atlas| atlas.problems.iter().cloned())
+        .chain(issues.issues.iter().map(|issue| format!("Skipped {issue}")))
+        .collect::<Vec<_>>();
+
+    let mut value = lines.iter().take(AssetWarnings::MAX_LINES).cloned().collect::<Vec<_>>().join("\n");
+    if lines.len() > AssetWarnings::MAX_LINES {
+        value += &format!("\n...and {} more; see the log", lines.len() - AssetWarnings::MAX_LINES);
+    }
+
+    if let Ok(mut text) = warnings.get_single_mut() {
+        text.sections[0].value = value;
+        return;
+    }
+
+    commands.spawn((
+        AssetWarnings,
+        TextBundle {
+            text: Text::from_section(value, TextStyle {
+                font: fonts.font.clone_weak(),
+                font_size: 16.,
+                color: AssetWarnings::COLOR,
+            }),
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(8.),
+                    top: Val::Px(8.),
+                    ..default()
+                },
+                ..default()
+            },
+            z_index: ZIndex::Global(i32::MAX),
+            ..default()
+        },
+    ));
+}
@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    ext::*,
+    GROUP_CHECKPOINT,
+    GenericSprites,
+    Cix, CixSpawnPos,
+    WorldObject,
+    Timed, Easing,
+};
+
+use std::ops::RangeInclusive as RangeIncl;
+
+/// Moves [`CixSpawnPos`] to itself once Cix touches it. Only the latest one touched stays active.
+#[derive(Component, Copy, Clone, Default)]
+pub struct Checkpoint {
+    pub active: bool,
+}
+
+impl Checkpoint {
+    pub const COLOR: RangeIncl<Color> = Color::rgba(1., 1., 1., 0.24)..=Color::rgba(0.4, 1.8, 3., 0.8);
+    pub const ACTIVATE_TIME: f64 = 0.8;
+    pub const RING_SIZE: RangeIncl<f32> = 32f32..=256f32;
+}
+
+/// The ring that expands out of a [`Checkpoint`] as it activates.
+#[derive(Component, Copy, Clone)]
+pub struct CheckpointActivation(pub Entity);
+
+pub fn spawn_checkpoint(
+    commands: &mut Commands,
+    sprites: &GenericSprites,
+    pos: Vec2,
+) {
+    commands.spawn((
+        WorldObject,
+        Checkpoint::default(),
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: sprites.square.index,
+                color: *Checkpoint::COLOR.start(),
+                custom_size: Some(Vec2::new(6., 64.)),
+                ..default()
+            },
+            texture_atlas: sprites.square.atlas.clone_weak(),
+            transform: Transform::from_translation(pos.extend(5.)),
+            ..default()
+        },
+        (
+            RigidBody::Fixed,
+            Sensor,
+            CollisionGroups::new(GROUP_CHECKPOINT, Group::ALL),
+            Collider::cuboid(16., 32.),
+        ),
+    )).with_children(|builder| { builder.spawn(
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: sprites.square.index,
+                color: *Checkpoint::COLOR.start(),
+                custom_size: Some(Vec2::splat(16.)),
+                ..default()
+            },
+            texture_atlas: sprites.square.atlas.clone_weak(),
+            transform: Transform::from_xyz(0., 44., 0.)
+                .with_rotation(Quat::from_axis_angle(Vec3::Z, f32::PI / 4.)),
+            ..default()
+        },
+    ); });
+}
+
+pub fn update_checkpoint_sys(
+    mut commands: Commands,
+    context: Res<RapierContext>,
+    mut cix_pos: ResMut<CixSpawnPos>,
+    cix: Query<Entity, With<Cix>>,
+    mut checkpoints: Query<(Entity, &mut Checkpoint, &GlobalTransform, &Children)>,
+    mut sprites: Query<&mut TextureAtlasSprite>,
+    generic_sprites: Res<GenericSprites>,
+) {
+    let Ok(cix) = cix.get_single() else { return };
+    let Some(touched) = checkpoints.iter()
+        .find(|&(e, &checkpoint, ..)| !checkpoint.active && context.intersection_pair(cix, e) == Some(true))
+        .map(|(e, ..)| e)
+    else { return };
+
+    for (e, mut checkpoint, &global_trns, children) in &mut checkpoints {
+        if e == touched {
+            checkpoint.active = true;
+
+            let pos = global_trns.translation().truncate();
+            **cix_pos = pos;
+
+            commands.spawn((
+                WorldObject,
+                CheckpointActivation(e),
+                Timed::new(Checkpoint::ACTIVATE_TIME),
+                SpriteSheetBundle {
+                    sprite: TextureAtlasSprite {
+                        index: generic_sprites.circle.index,
+                        color: *Checkpoint::COLOR.end(),
+                        custom_size: Some(Vec2::splat(*Checkpoint::RING_SIZE.start())),
+                        ..default()
+                    },
+                    texture_atlas: generic_sprites.circle.atlas.clone_weak(),
+                    transform: Transform::from_translation(pos.extend(45.)),
+                    ..default()
+                },
+            ));
+        } else if checkpoint.active {
+            checkpoint.active = false;
+            for &target in [e].iter().chain(children.iter()) {
+                if let Ok(mut sprite) = sprites.get_mut(target) {
+                    sprite.color = *Checkpoint::COLOR.start();
+                }
+            }
+        }
+    }
+}
+
+pub fn update_checkpoint_activation_sys(
+    mut activations: Query<(&CheckpointActivation, &Timed, &mut TextureAtlasSprite)>,
+    checkpoints: Query<(&Checkpoint, &Children)>,
+    mut sprites: Query<&mut TextureAtlasSprite, Without<CheckpointActivation>>,
+) {
+    for (&CheckpointActivation(e), &timed, mut sprite) in &mut activations {
+        let f = timed.ease(Easing::QuadOut);
+        sprite.color = Checkpoint::COLOR.end().lerp(Color::NONE, timed.ease(Easing::QuadIn));
+        sprite.custom_size = Some(Vec2::splat(Checkpoint::RING_SIZE.start().lerp(*Checkpoint::RING_SIZE.end(), f)));
+
+        let Ok((&checkpoint, children)) = checkpoints.get(e) else { continue };
+        if !checkpoint.active { continue };
+
+        for &target in [e].iter().chain(children.iter()) {
+            if let Ok(mut sprite) = sprites.get_mut(target) {
+                sprite.color = Checkpoint::COLOR.start().lerp(*Checkpoint::COLOR.end(), f);
+            }
+        }
+    }
+}
@@ -6,7 +6,6 @@ use bevy::{
 use crate::{
     CameraPos,
     GenericSprites,
-    GameAtlas,
 };
 
 #[derive(Component)]
@@ -18,10 +17,9 @@ pub fn world_fade_add_sys(
     primary_window: Query<Entity, With<PrimaryWindow>>,
     windows: Query<(Entity, &Window)>,
     images: Res<Assets<Image>>,
-    atlases: Res<Assets<TextureAtlas>>,
-    sprites: Res<GenericSprites>, atlas: Res<GameAtlas>,
+    sprites: Res<GenericSprites>,
 ) {
-    let (camera, proj, &camera_trns) = camera.single();
+    let Ok((camera, proj, &camera_trns)) = camera.get_single() else { return };
     let camera_trns = camera_trns.translation();
 
     commands.spawn((
@@ -29,7 +27,7 @@ pub fn world_fade_add_sys(
         SpriteSheetBundle {
             sprite: TextureAtlasSprite {
                 color: Color::BLACK,
-                index: atlas.index(&atlases, &sprites.square),
+                index: sprites.square.index,
                 custom_size: camera.target
                     .normalize(primary_window.get_single().ok())
                     .and_then(|target| target.get_render_target_info(&windows, &images))
@@ -42,7 +40,7 @@ pub fn world_fade_add_sys(
                     }),
                 ..default()
             },
-            texture_atlas: atlas.clone_weak(),
+            texture_atlas: sprites.square.atlas.clone_weak(),
             transform: Transform::from_xyz(camera_trns.x, camera_trns.y, camera_trns.z - 0.1),
             ..default()
         },
@@ -57,8 +55,8 @@ pub fn world_fade_update_sys(
     images: Res<Assets<Image>>,
     mut fade: Query<(&mut Transform, &mut TextureAtlasSprite), With<WorldFade>>,
 ) {
-    let (camera, proj, &camera_trns) = camera.single();
-    let (mut trns, mut sprite) = fade.single_mut();
+    let Ok((camera, proj, &camera_trns)) = camera.get_single() else { return };
+    let Ok((mut trns, mut sprite)) = fade.get_single_mut() else { return };
 
     if
         let Some(target) = camera.target.normalize(primary_window.get_single().ok()) &&
@@ -7,8 +7,9 @@ use bevy_rapier2d::prelude::*;
 use crate::{
     ext::*,
     GROUP_STOP_PIERCE,
-    EnvironmentSprites, GenericSprites, GameAtlas,
+    EnvironmentSprites, GenericSprites,
     WorldObject,
+    GameTime,
 };
 
 #[derive(Component)]
@@ -16,8 +17,7 @@ pub struct Flower;
 
 pub fn spawn_flower(
     commands: &mut Commands,
-    atlases: &Assets<TextureAtlas>,
-    env_sprites: &EnvironmentSprites, gen_sprites: &GenericSprites, atlas: &GameAtlas,
+    env_sprites: &EnvironmentSprites, gen_sprites: &GenericSprites,
     pos: Vec2,
 ) {
     commands.spawn((
@@ -25,12 +25,12 @@ pub fn spawn_flower(
         Flower,
         SpriteSheetBundle {
             sprite: TextureAtlasSprite {
-                index: atlas.index(atlases, &gen_sprites.circle),
+                index: gen_sprites.circle.index,
                 color: Color::rgba(0.98, 1., 0.52, 1.),
                 custom_size: Some(Vec2::splat(32.)),
                 ..default()
             },
-            texture_atlas: atlas.clone_weak(),
+            texture_atlas: gen_sprites.circle.atlas.clone_weak(),
             transform: Transform::from_translation(pos.extend(40.)),
             ..default()
         },
@@ -45,11 +45,11 @@ pub fn spawn_flower(
             let angle = (360. / 5. * i as f32).to_radians();
             builder.spawn(SpriteSheetBundle {
                 sprite: TextureAtlasSprite {
-                    index: atlas.index(atlases, &env_sprites.petal),
+                    index: env_sprites.petal.index,
                     anchor: Anchor::Custom(Vec2::new(0., -0.5)),
                     ..default()
                 },
-                texture_atlas: atlas.clone_weak(),
+                texture_atlas: env_sprites.petal.atlas.clone_weak(),
                 transform: Transform::from_translation((Vec2::from_angle(angle) * 12.).extend(-1.))
                     .with_rotation(Quat::from_axis_angle(Vec3::Z, angle - f32::PI / 2.)),
                 ..default()
@@ -59,7 +59,7 @@ pub fn spawn_flower(
 }
 
 pub fn update_flower_sys(
-    time: Res<Time>,
+    time: Res<GameTime>,
     flower: Query<&Children, With<Flower>>,
     mut petals: Query<&mut TextureAtlasSprite>,
 ) {
@@ -4,7 +4,7 @@ use bevy_rapier2d::prelude::*;
 
 use crate::{
     GROUP_GATE,
-    EnvironmentSprites, GameAtlas,
+    EnvironmentSprites,
     CixAction, CixActState,
     WorldStart, WorldObject,
     Timed,
@@ -17,16 +17,15 @@ pub struct Gate {
 
 pub fn spawn_gate(
     commands: &mut Commands,
-    atlases: &Assets<TextureAtlas>,
-    sprites: &EnvironmentSprites, atlas: &GameAtlas,
+    sprites: &EnvironmentSprites,
     level: String, pos: Vec2,
 ) {
     commands.spawn((
         WorldObject,
         Gate { level },
         SpriteSheetBundle {
-            sprite: TextureAtlasSprite::new(atlas.index(atlases, &sprites.gate)),
-            texture_atlas: atlas.clone_weak(),
+            sprite: TextureAtlasSprite::new(sprites.gate.index),
+            texture_atlas: sprites.gate.atlas.clone_weak(),
             transform: Transform::from_translation(pos.extend(5.)),
             ..default()
         },
@@ -54,17 +53,27 @@ pub fn update_gate_sys(
     let mut level = level.single_mut();
     for (e, gate) in &gates {
         if let Some(true) = context.intersection_pair(cix, e) {
-            for object in &objects {
-                commands.entity(object).despawn_recursive();
-            }
-
-            commands.spawn((
-                WorldStart,
-                Timed::new(WorldStart::FADE_DURATION),
-            ));
-
-            *level = LevelSet::from_iid(gate.level.clone());
+            goto_level(&mut commands, &mut level, &objects, gate.level.clone());
             continue;
         }
     }
 }
+
+/// Despawns every [`WorldObject`] and swaps `level_set` over to `iid`, fading in like the start of the game.
+pub fn goto_level(
+    commands: &mut Commands,
+    level_set: &mut LevelSet,
+    objects: impl IntoIterator<Item = Entity>,
+    iid: String,
+) {
+    for object in objects {
+        commands.entity(object).despawn_recursive();
+    }
+
+    commands.spawn((
+        WorldStart,
+        Timed::new(WorldStart::FADE_DURATION),
+    ));
+
+    *level_set = LevelSet::from_iid(iid);
+}
@@ -10,40 +10,267 @@ use bevy_ecs_tilemap::{
     prelude::*,
     helpers::square_grid::neighbors::Neighbors,
 };
-use bevy_rapier2d::prelude::*;
+use bevy_rapier2d::{
+    prelude::*,
+    plugin::systems::sync_removals,
+};
 
 use crate::{
     ext::*,
-    GROUP_STOP_PIERCE, GROUP_GROUND,
-    EnvironmentSprites, GenericSprites, StaticEnemySprites, GameAtlas,
-    Cix,
+    PIXELS_PER_METER, GROUP_STOP_PIERCE, GROUP_GROUND,
+    GameStates, EndStates, PauseStates, FixedSet,
+    EnvironmentSprites, GenericSprites, StaticEnemySprites,
+    Cix, CixSpawn, CixRespawn,
     LdtkWorld, BackgroundImages,
     CameraPos, CixSpawnPos, CixStates,
     EnemyGears,
-    Timed,
+    GameTime, GameRng, game_time_update_sys,
+    Timed, Easing, timed_update_sys, timed_post_update_sys,
+    TweenDoneEvent, tween_sys,
+    DeathEvent, health_update_sys, health_post_update_sys,
+    GodMode, collide_sys,
+    GameAtlas, AnimationEvent, animated_sprite_sys,
+    Lang, typewriter_sys,
 };
 
+mod checkpoint;
 mod end;
 mod fade;
 mod flower;
 mod gate;
-mod prelude;
+mod schema;
+mod sign;
 
+pub use checkpoint::*;
 pub use end::*;
 pub use fade::*;
 pub use flower::*;
 pub use gate::*;
-pub use prelude::*;
+pub use schema::*;
+pub use sign::*;
+
+pub struct WorldPlugin;
+impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_state::<EndStates>()
+            .add_state::<PauseStates>()
+
+            .insert_resource(FixedTime::new_from_secs(GameTime::STEP as f32))
+            .insert_resource(RapierConfiguration {
+                gravity: Vec2::new(0., -9.81 * PIXELS_PER_METER),
+                timestep_mode: TimestepMode::Fixed {
+                    dt: GameTime::STEP as f32,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .insert_resource(LdtkSettings {
+                set_clear_color: SetClearColor::No,
+                int_grid_rendering: IntGridRendering::Invisible,
+                level_background: LevelBackground::Nonexistent,
+                ..default()
+            })
+            .insert_resource(CameraPos(Vec2::splat(0.)))
+            .init_resource::<WorldStartLevel>()
+            .init_resource::<WorldStartSpawn>()
+            .init_resource::<WorldIssues>()
+            .init_resource::<GodMode>()
+            .init_resource::<GameTime>()
+            .init_resource::<GameRng>()
+
+            .edit_schedule(CoreSchedule::FixedUpdate, |schedule| { schedule
+                .configure_sets((
+                    FixedSet::First,
+                    FixedSet::PreUpdate,
+                    FixedSet::PreUpdateFlush,
+                    FixedSet::Update,
+                    FixedSet::UpdateFlush,
+                    PhysicsSet::SyncBackend,
+                    PhysicsSet::SyncBackendFlush,
+                    PhysicsSet::StepSimulation,
+                    PhysicsSet::Writeback,
+                    FixedSet::PostUpdate,
+                    FixedSet::PostUpdateFlush,
+                    FixedSet::StateTransitions,
+                ).chain())
+                .add_systems((
+                    apply_system_buffers.in_base_set(FixedSet::PreUpdateFlush),
+                    apply_system_buffers.in_base_set(FixedSet::UpdateFlush),
+                    apply_system_buffers.in_base_set(FixedSet::PostUpdateFlush),
+                    apply_state_transition::<CixStates>.in_base_set(FixedSet::StateTransitions),
+                    apply_state_transition::<EndStates>.in_base_set(FixedSet::StateTransitions),
+                ));
+
+                for set in [
+                    FixedSet::First,
+                    FixedSet::PreUpdate,
+                    FixedSet::PreUpdateFlush,
+                    FixedSet::Update,
+                    FixedSet::UpdateFlush,
+                    FixedSet::PostUpdate,
+                    FixedSet::PostUpdateFlush,
+                    FixedSet::StateTransitions,
+                ] {
+                    schedule.configure_set(set.run_if(in_state(PauseStates::Running)));
+                }
+
+                for set in [
+                    PhysicsSet::SyncBackend,
+                    PhysicsSet::SyncBackendFlush,
+                    PhysicsSet::StepSimulation,
+                    PhysicsSet::Writeback,
+                ] {
+                    schedule.configure_set(set.run_if(in_state(PauseStates::Running)));
+                }
+            })
+
+            .add_plugin(LdtkPlugin)
+            .add_plugin(RapierPhysicsPlugin::<()>::pixels_per_meter(PIXELS_PER_METER).with_default_system_setup(false))
+            .add_systems(RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::SyncBackend)
+                .in_base_set(PhysicsSet::SyncBackend)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems(RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::SyncBackendFlush)
+                .in_base_set(PhysicsSet::SyncBackendFlush)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems(RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::StepSimulation)
+                .in_base_set(PhysicsSet::StepSimulation)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems(RapierPhysicsPlugin::<()>::get_systems(PhysicsSet::Writeback)
+                .in_base_set(PhysicsSet::Writeback)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            // Removal events only live for two frames, which may pass without a single fixed step.
+            .add_system(sync_removals.in_base_set(CoreSet::Last))
+
+            .add_event::<DeathEvent>()
+            .add_event::<WorldRestartEvent>()
+            .add_event::<AnimationEvent>()
+            .add_event::<TweenDoneEvent>()
+
+            // Only time spent playing counts, so saves don't include the menus, the prelude or the ending.
+            .add_system(game_time_update_sys
+                .in_base_set(FixedSet::First)
+                .run_if(in_state(GameStates::Gameplay))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems((timed_update_sys, health_update_sys)
+                .in_base_set(FixedSet::PreUpdate)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(tween_sys
+                .in_base_set(FixedSet::Update)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems((timed_post_update_sys, health_post_update_sys)
+                .in_base_set(FixedSet::PostUpdate)
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(animated_sprite_sys
+                .in_base_set(FixedSet::PostUpdate)
+                .run_if(resource_exists::<GameAtlas>())
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+
+            .add_system(on_end_sys.in_schedule(OnEnter(EndStates::Yes)))
+            .add_system(end_update_sys
+                .in_base_set(FixedSet::Update)
+                .run_if(in_state(EndStates::Yes))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+
+            .add_systems((world_start_sys, world_fade_add_sys).in_schedule(OnEnter(GameStates::Gameplay)))
+            .add_system(world_exit_sys.in_schedule(OnExit(GameStates::Gameplay)))
+            .add_system(world_validate_sys
+                .in_base_set(CoreSet::PreUpdate)
+                .before(world_post_start_sys)
+                .run_if(resource_exists::<LdtkWorld>())
+            )
+            .add_system(world_post_start_sys
+                .in_base_set(CoreSet::PreUpdate)
+                .run_if(in_state(GameStates::Gameplay))
+            )
+            .add_systems((
+                world_fade_update_sys,
+                world_update_bg_sys,
+                world_restart_sys,
+            ).in_set(OnUpdate(GameStates::Gameplay)))
+            .add_systems((
+                update_sign_sys.before(typewriter_sys),
+                update_sign_box_sys.after(typewriter_sys),
+            )
+                .in_set(OnUpdate(GameStates::Gameplay))
+                .distributive_run_if(in_state(PauseStates::Running))
+            )
+            .add_system(collide_sys
+                .in_base_set(FixedSet::Update)
+                .run_if(in_state(GameStates::Gameplay))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(world_start_update_sys
+                .in_base_set(FixedSet::Update)
+                .run_if(in_state(GameStates::Gameplay))
+                .run_if(in_state(CixStates::Nonexistent))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_systems((
+                update_gate_sys,
+                update_flower_sys,
+                update_checkpoint_sys,
+            )
+                .in_base_set(FixedSet::Update)
+                .distributive_run_if(in_state(CixStates::Alive))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            )
+            .add_system(update_checkpoint_activation_sys
+                .in_base_set(FixedSet::Update)
+                .run_if(in_state(GameStates::Gameplay))
+                .in_schedule(CoreSchedule::FixedUpdate)
+            );
+
+        #[cfg(feature = "dev")]
+        app.add_system(world_reload_sys.in_set(OnUpdate(GameStates::Gameplay)));
+    }
+}
 
 #[derive(Component)]
 pub struct WorldStart;
 impl WorldStart {
     pub const FADE_DURATION: f64 = 2.;
+    pub const FIRST_LEVEL: &'static str = "4beeb010-c640-11ed-97c1-772602c34051";
+}
+
+/// The iid of the level [`world_start_sys`] loads into.
+#[derive(Resource, Deref, DerefMut, Clone)]
+pub struct WorldStartLevel(pub String);
+impl Default for WorldStartLevel {
+    #[inline]
+    fn default() -> Self {
+        Self(WorldStart::FIRST_LEVEL.into())
+    }
 }
 
+/// Where Cix first spawns in the [`WorldStartLevel`] instead of its `cix` entity, e.g. a saved checkpoint. Taken as
+/// soon as the level spawns, so restarting the level still starts from the `cix` entity.
+#[derive(Resource, Deref, DerefMut, Copy, Clone, Default)]
+pub struct WorldStartSpawn(pub Option<Vec2>);
+
+/// Present once [`world_post_start_sys`] has spawned the level's objects and colliders.
 #[derive(Resource)]
 pub struct WorldInit;
 
+/// Present while the level respawns in place after `worlds/world.ldtk` changed on disk. Only ever inserted with the
+/// `dev` feature.
+#[derive(Resource)]
+pub struct WorldReload;
+
+/// Respawns the current level from scratch, with Cix back at its start.
+#[derive(Copy, Clone)]
+pub struct WorldRestartEvent;
+
 #[derive(Component)]
 pub struct WorldBackground(pub f32);
 
@@ -53,6 +280,7 @@ pub struct WorldObject;
 pub fn world_start_sys(
     mut commands: Commands,
     world: Res<LdtkWorld>, bg: Res<BackgroundImages>,
+    level: Res<WorldStartLevel>,
 ) {
     commands.spawn((
         WorldBackground(0.1),
@@ -82,7 +310,7 @@ pub fn world_start_sys(
 
     commands.spawn(LdtkWorldBundle {
         ldtk_handle: world.clone_weak(),
-        level_set: LevelSet::from_iid("4beeb010-c640-11ed-97c1-772602c34051"),
+        level_set: LevelSet::from_iid((**level).clone()),
         ..default()
     });
 
@@ -92,6 +320,78 @@ pub fn world_start_sys(
     ));
 }
 
+pub fn world_restart_sys(
+    mut commands: Commands,
+    mut events: EventReader<WorldRestartEvent>,
+    mut state: ResMut<NextState<CixStates>>,
+    world: Query<Entity, With<LevelSet>>,
+    despawned: Query<Entity, Or<(With<WorldObject>, With<WorldStart>, With<Cix>, With<CixSpawn>, With<CixRespawn>)>>,
+) {
+    if events.is_empty() { return };
+    events.clear();
+
+    // There's no world to respawn while one level is being swapped for another.
+    let Ok(world) = world.get_single() else { return };
+    for e in &despawned {
+        commands.entity(e).despawn_recursive();
+    }
+
+    commands.entity(world).insert(Respawn);
+    commands.spawn((
+        WorldStart,
+        Timed::new(WorldStart::FADE_DURATION),
+    ));
+
+    commands.remove_resource::<WorldInit>();
+    commands.remove_resource::<WorldReload>();
+    state.set(CixStates::Nonexistent);
+}
+
+/// Tears the whole world down, so entering [`GameStates::Gameplay`] again starts from nothing.
+pub fn world_exit_sys(
+    mut commands: Commands,
+    mut gears: ResMut<EnemyGears>,
+    mut cix_state: ResMut<NextState<CixStates>>,
+    mut end_state: ResMut<NextState<EndStates>>,
+    mut pause_state: ResMut<NextState<PauseStates>>,
+    despawned: Query<Entity, Or<(
+        With<LevelSet>, With<WorldBackground>, With<WorldFade>, With<WorldObject>, With<WorldStart>,
+        With<Cix>, With<Timed>,
+    )>>,
+) {
+    for e in &despawned {
+        commands.entity(e).despawn_recursive();
+    }
+
+    gears.clear();
+    commands.remove_resource::<WorldInit>();
+    commands.remove_resource::<WorldReload>();
+    cix_state.set(CixStates::Nonexistent);
+    end_state.set(EndStates::No);
+    pause_state.set(PauseStates::Running);
+}
+
+/// Respawns the current level once `worlds/world.ldtk` is modified, keeping Cix where it is. The LDtk plugin respawns
+/// the levels by itself; this clears out what [`world_post_start_sys`] spawned alongside them, so it spawns it anew.
+#[cfg(feature = "dev")]
+pub fn world_reload_sys(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<LdtkAsset>>,
+    world: Res<LdtkWorld>,
+    mut gears: ResMut<EnemyGears>,
+    despawned: Query<Entity, With<WorldObject>>,
+) {
+    if !events.iter().any(|event| matches!(event, AssetEvent::Modified { handle } if *handle == **world)) { return };
+
+    for e in &despawned {
+        commands.entity(e).despawn_recursive();
+    }
+
+    gears.clear();
+    commands.remove_resource::<WorldInit>();
+    commands.insert_resource(WorldReload);
+}
+
 pub fn world_update_bg_sys(
     camera_pos: Res<CameraPos>,
     camera: Query<(&Camera, &OrthographicProjection)>,
@@ -100,7 +400,7 @@ pub fn world_update_bg_sys(
     images: Res<Assets<Image>>,
     mut backgrounds: Query<(&WorldBackground, &mut Sprite, &mut Transform)>,
 ) {
-    let (camera, proj) = camera.single();
+    let Ok((camera, proj)) = camera.get_single() else { return };
     let Some(size) = camera.target
         .normalize(primary_window.get_single().ok())
         .and_then(|target| target.get_render_target_info(&windows, &images))
@@ -128,86 +428,76 @@ pub fn world_update_bg_sys(
 pub fn world_post_start_sys(
     mut commands: Commands,
     mut camera_pos: ResMut<CameraPos>, mut cix_pos: ResMut<CixSpawnPos>,
+    (mut start_spawn, reload): (ResMut<WorldStartSpawn>, Option<Res<WorldReload>>),
+    issues: Res<WorldIssues>,
     mut gears: ResMut<EnemyGears>,
     added_entities: Query<(&EntityInstance, &GlobalTransform), Added<EntityInstance>>,
     added_tiles: Query<(Entity, &TilemapId, &TilePos, &IntGridCell, &GlobalTransform), Added<IntGridCell>>,
     tiles: Query<&IntGridCell>,
     tilemaps: Query<(&LayerMetadata, &TileStorage)>,
-    atlases: Res<Assets<TextureAtlas>>,
-    env_sprites: Res<EnvironmentSprites>, gen_sprites: Res<GenericSprites>, enemy_sprites: Res<StaticEnemySprites>, atlas: Res<GameAtlas>,
-    start: Query<(), Added<WorldStart>>,
+    (env_sprites, gen_sprites, enemy_sprites): (Res<EnvironmentSprites>, Res<GenericSprites>, Res<StaticEnemySprites>),
+    lang: Lang,
+    init: Option<Res<WorldInit>>,
     mut cix: Query<&mut Transform, With<Cix>>,
-    mut has_started: Local<bool>,
 ) {
-    if start.get_single().is_ok() {
-        *has_started = false;
-    }
-
-    if *has_started { return };
+    if init.is_some() { return };
 
     let mut started = false;
     for (inst, &trns) in &added_entities {
         if !started { started = true; }
+        if issues.skipped.contains(&inst.iid) { continue };
 
         let pos = trns.translation().truncate();
         match inst.identifier.as_ref() {
+            // Reloading leaves Cix, the camera and the checkpoint where they were.
+            "cix" if reload.is_some() => {},
             "cix" => {
+                let pos = start_spawn.take().unwrap_or(pos);
                 **camera_pos = pos;
                 **cix_pos = pos;
                 if let Ok(mut trns) = cix.get_single_mut() {
                     trns.translation = cix_pos.extend(trns.translation.z);
                 }
             },
+            // Entities that made it past `WorldIssues` have all their fields, so the `continue`s never happen.
             "barrier" => {
-                let FieldValue::Float(Some(height)) = inst.field_instances.iter()
-                    .find(|inst| &inst.identifier == "height").unwrap()
-                    .value
-                else { unreachable!() };
-                let FieldValue::Color(color) = inst.field_instances.iter()
-                    .find(|inst| &inst.identifier == "color").unwrap()
-                    .value
-                else { unreachable!() };
+                let (Some(height), Some(color)) = (inst.float("height"), inst.color("color")) else { continue };
 
                 crate::spawn_enemy_barrier(
                     &mut commands,
                     height, color,
                     pos,
-                    &atlases,
-                    &enemy_sprites, &atlas,
+                    &enemy_sprites,
                 );
             },
             "gear" => {
-                let FieldValue::EntityRef(ref reference) = inst.field_instances.iter()
-                    .find(|inst| &inst.identifier == "link").unwrap()
-                    .value
-                else { unreachable!() };
-                let FieldValue::Float(Some(diameter)) = inst.field_instances.iter()
-                    .find(|inst| &inst.identifier == "diameter").unwrap()
-                    .value
-                else { unreachable!() };
-                let FieldValue::Color(color) = inst.field_instances.iter()
-                    .find(|inst| &inst.identifier == "color").unwrap()
-                    .value
-                else { unreachable!() };
+                let (Some(reference), Some(diameter), Some(color)) = (
+                    inst.entity_ref("link"),
+                    inst.float("diameter"),
+                    inst.color("color"),
+                ) else { continue };
 
                 gears.insert(inst.iid.clone(), crate::spawn_enemy_gear(
                     &mut commands,
                     diameter, color,
-                    reference.as_ref().map(|r| r.entity_iid.clone()),
+                    reference.map(|r| r.entity_iid.clone()),
                     pos,
-                    &atlases,
-                    &enemy_sprites, &atlas,
+                    &enemy_sprites,
                 ));
             },
             "gate" => {
-                let FieldValue::String(Some(ref iid)) = inst.field_instances.iter()
-                    .find(|inst| &inst.identifier == "level").unwrap()
-                    .value
-                else { unreachable!() };
-                spawn_gate(&mut commands, &atlases, &env_sprites, &atlas, iid.clone(), pos);
+                let Some(iid) = inst.string("level") else { continue };
+                spawn_gate(&mut commands, &env_sprites, iid.into(), pos);
             },
             "flower" => {
-                spawn_flower(&mut commands, &atlases, &env_sprites, &gen_sprites, &atlas, pos);
+                spawn_flower(&mut commands, &env_sprites, &gen_sprites, pos);
+            },
+            "checkpoint" => {
+                spawn_checkpoint(&mut commands, &gen_sprites, pos);
+            },
+            "sign" => {
+                let Some(text) = inst.string("text") else { continue };
+                spawn_sign(&mut commands, &gen_sprites, lang.font(), text.into(), pos);
             },
             _ => {},
         }
@@ -302,7 +592,11 @@ pub fn world_post_start_sys(
 
     let mut iterated = HashSet::default();
     let mut flatmost = Vec::new();
-    for &pos in flat.keys() {
+    // Hash map order differs between runs; sort so the colliders reach Rapier in the same order every time.
+    let mut positions = flat.keys().copied().collect::<Vec<_>>();
+    positions.sort_unstable_by_key(|pos| (pos.y, pos.x));
+
+    for pos in positions {
         if !iterated.insert(pos) { continue };
 
         let mut leftmost = pos;
@@ -336,8 +630,8 @@ pub fn world_post_start_sys(
     }
 
     if started {
-        *has_started = true;
         commands.insert_resource(WorldInit);
+        commands.remove_resource::<WorldReload>();
     }
 }
 
@@ -353,11 +647,9 @@ pub fn world_start_update_sys(
         return;
     }
 
-    let mut fade = fade.single_mut();
-
-    let mut f = timed.fin();
-    f = f * f * (3. - 2. * f);
-    fade.color = Color::BLACK.lerp(Color::NONE, f);
+    if let Ok(mut fade) = fade.get_single_mut() {
+        fade.color = Color::BLACK.lerp(Color::NONE, timed.ease(Easing::SmoothStep));
+    }
 
     if timed.ended() {
         state.set(CixStates::Spawning);
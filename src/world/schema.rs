@@ -0,0 +1,238 @@
+use bevy::{
+    prelude::*,
+    utils::{
+        HashSet, HashMap,
+    },
+};
+use bevy_ecs_ldtk::{
+    prelude::*,
+    ldtk::{
+        LdtkJson, FieldInstanceEntityReference,
+    },
+};
+
+use crate::LdtkWorld;
+
+use std::fmt;
+
+/// What a field of an LDtk entity must hold.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum FieldKind {
+    Float,
+    Color,
+    String,
+    /// A string holding the iid of a level in the world.
+    LevelIid,
+    /// A reference to an entity with the given identifier.
+    EntityRef(&'static str),
+}
+
+impl FieldKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Float => "Float",
+            Self::Color => "Color",
+            Self::String | Self::LevelIid => "String",
+            Self::EntityRef(_) => "EntityRef",
+        }
+    }
+
+    /// `None` if `value` is of another type, otherwise whether it's null.
+    pub fn check(self, value: &FieldValue) -> Option<bool> {
+        match (self, value) {
+            (Self::Float, FieldValue::Float(value)) => Some(value.is_none()),
+            (Self::Color, FieldValue::Color(_)) => Some(false),
+            (Self::String | Self::LevelIid, FieldValue::String(value)) => Some(value.is_none()),
+            (Self::EntityRef(_), FieldValue::EntityRef(value)) => Some(value.is_none()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct FieldSchema {
+    pub name: &'static str,
+    pub kind: FieldKind,
+    pub nullable: bool,
+}
+
+impl FieldSchema {
+    #[inline]
+    pub const fn new(name: &'static str, kind: FieldKind) -> Self {
+        Self { name, kind, nullable: false, }
+    }
+
+    #[inline]
+    pub const fn nullable(mut self) -> Self {
+        self.nullable = true;
+        self
+    }
+}
+
+/// The fields every entity [`world_post_start_sys`](crate::world_post_start_sys) spawns must have. Entities with other
+/// identifiers are reported too, as they'd silently do nothing.
+pub const ENTITY_SCHEMAS: &[(&str, &[FieldSchema])] = &[
+    ("cix", &[]),
+    ("barrier", &[
+        FieldSchema::new("height", FieldKind::Float),
+        FieldSchema::new("color", FieldKind::Color),
+    ]),
+    ("gear", &[
+        FieldSchema::new("link", FieldKind::EntityRef("gear")).nullable(),
+        FieldSchema::new("diameter", FieldKind::Float),
+        FieldSchema::new("color", FieldKind::Color),
+    ]),
+    ("gate", &[
+        FieldSchema::new("level", FieldKind::LevelIid),
+    ]),
+    ("flower", &[]),
+    ("checkpoint", &[]),
+    ("sign", &[
+        FieldSchema::new("text", FieldKind::String),
+    ]),
+];
+
+/// One LDtk entity that doesn't match [`ENTITY_SCHEMAS`].
+#[derive(Clone, Debug)]
+pub struct WorldIssue {
+    pub level: String,
+    pub entity: String,
+    pub iid: String,
+    pub grid: IVec2,
+    pub message: String,
+}
+
+impl fmt::Display for WorldIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{}: {} {} at ({}, {}): {}",
+            self.level, self.entity, self.iid, self.grid.x, self.grid.y, self.message,
+        )
+    }
+}
+
+/// Everything wrong with the loaded world, and the iids of the entities that are skipped because of it.
+#[derive(Resource, Clone, Default)]
+pub struct WorldIssues {
+    pub issues: Vec<WorldIssue>,
+    pub skipped: HashSet<String>,
+}
+
+impl WorldIssues {
+    pub fn validate(project: &LdtkJson) -> Self {
+        let levels = project.iter_levels().map(|level| level.iid.as_str()).collect::<HashSet<_>>();
+        let entities = project.iter_levels()
+            .flat_map(|level| level.layer_instances.iter().flatten())
+            .flat_map(|layer| &layer.entity_instances)
+            .map(|inst| (inst.iid.as_str(), inst.identifier.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let mut issues = Vec::new();
+        for level in project.iter_levels() {
+            for inst in level.layer_instances.iter().flatten().flat_map(|layer| &layer.entity_instances) {
+                let mut report = |message: String| issues.push(WorldIssue {
+                    level: level.identifier.clone(),
+                    entity: inst.identifier.clone(),
+                    iid: inst.iid.clone(),
+                    grid: inst.grid,
+                    message,
+                });
+
+                let Some(&(_, fields)) = ENTITY_SCHEMAS.iter().find(|&&(id, _)| id == inst.identifier) else {
+                    report("unknown entity".into());
+                    continue;
+                };
+
+                for field in fields {
+                    let Some(value) = inst.field(field.name) else {
+                        report(format!("missing field `{}`", field.name));
+                        continue;
+                    };
+
+                    match field.kind.check(value) {
+                        None => report(format!("field `{}` should be {}", field.name, field.kind.name())),
+                        Some(true) if !field.nullable => report(format!("field `{}` is null", field.name)),
+                        Some(_) => match (field.kind, value) {
+                            (FieldKind::LevelIid, FieldValue::String(Some(iid))) if !levels.contains(iid.as_str()) =>
+                                report(format!("field `{}` names no level: {iid}", field.name)),
+                            (FieldKind::EntityRef(target), FieldValue::EntityRef(Some(reference))) => {
+                                match entities.get(reference.entity_iid.as_str()) {
+                                    None => report(format!("field `{}` refers to no entity: {}", field.name, reference.entity_iid)),
+                                    Some(&id) if id != target => report(format!("field `{}` refers to a {id}, not a {target}", field.name)),
+                                    Some(_) => {},
+                                }
+                            },
+                            _ => {},
+                        },
+                    }
+                }
+            }
+        }
+
+        let skipped = issues.iter().map(|issue| issue.iid.clone()).collect();
+        Self { issues, skipped, }
+    }
+}
+
+/// Typed access to an LDtk entity's fields, `None` if there's no such field or it holds something else.
+pub trait EntityFields {
+    fn field(&self, name: &str) -> Option<&FieldValue>;
+
+    #[inline]
+    fn float(&self, name: &str) -> Option<f32> {
+        match self.field(name)? {
+            &FieldValue::Float(value) => value,
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn color(&self, name: &str) -> Option<Color> {
+        match self.field(name)? {
+            &FieldValue::Color(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn string(&self, name: &str) -> Option<&str> {
+        match self.field(name)? {
+            FieldValue::String(value) => value.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// `Some(None)` for a null reference.
+    #[inline]
+    fn entity_ref(&self, name: &str) -> Option<Option<&FieldInstanceEntityReference>> {
+        match self.field(name)? {
+            FieldValue::EntityRef(value) => Some(value.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl EntityFields for EntityInstance {
+    #[inline]
+    fn field(&self, name: &str) -> Option<&FieldValue> {
+        self.field_instances.iter()
+            .find(|field| field.identifier == name)
+            .map(|field| &field.value)
+    }
+}
+
+/// Validates the world once it's loaded, and again whenever it's reloaded.
+pub fn world_validate_sys(
+    world: Res<LdtkWorld>, assets: Res<Assets<LdtkAsset>>,
+    mut events: EventReader<AssetEvent<LdtkAsset>>,
+    mut issues: ResMut<WorldIssues>,
+) {
+    let modified = events.iter().any(|event| matches!(event, AssetEvent::Modified { handle } if *handle == **world));
+    if !world.is_added() && !modified { return };
+
+    let Some(asset) = assets.get(&**world) else { return };
+    *issues = WorldIssues::validate(&asset.project);
+    for issue in &issues.issues {
+        warn!("Skipping LDtk entity; {issue}");
+    }
+}
@@ -0,0 +1,189 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    GROUP_SIGN,
+    GenericSprites,
+    Lang, Settings,
+    CixAction, CixActState, CixBinding,
+    WorldObject,
+    Typewriter, TypewriterPressEvent, TypewriterDoneEvent,
+};
+
+/// Shows its text in a [`SignBox`] when Cix presses [`CixAction::Interact`] within range.
+#[derive(Component)]
+pub struct Sign {
+    pub text: String,
+}
+
+impl Sign {
+    pub const COLOR: Color = Color::rgba(1., 1., 1., 0.24);
+    pub const PROMPT_COLOR: Color = Color::rgba(1., 1., 1., 0.8);
+    pub const RANGE: Vec2 = Vec2::new(48., 48.);
+}
+
+#[derive(Component)]
+pub struct SignPrompt;
+
+/// The text box of the [`Sign`] being read, with `text` holding its [`Typewriter`]. Pressing
+/// [`CixAction::Interact`] again completes or turns the page; walking out of range closes it.
+#[derive(Component, Copy, Clone)]
+pub struct SignBox {
+    pub sign: Entity,
+    pub text: Entity,
+}
+
+pub fn spawn_sign(
+    commands: &mut Commands,
+    sprites: &GenericSprites,
+    font: Handle<Font>,
+    text: String, pos: Vec2,
+) {
+    commands.spawn((
+        WorldObject,
+        Sign { text },
+        SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: sprites.square.index,
+                color: Sign::COLOR,
+                custom_size: Some(Vec2::new(40., 24.)),
+                ..default()
+            },
+            texture_atlas: sprites.square.atlas.clone_weak(),
+            transform: Transform::from_translation(pos.extend(5.)),
+            ..default()
+        },
+        (
+            RigidBody::Fixed,
+            Sensor,
+            CollisionGroups::new(GROUP_SIGN, Group::ALL),
+            Collider::cuboid(Sign::RANGE.x, Sign::RANGE.y),
+        ),
+    )).with_children(|builder| {
+        builder.spawn(SpriteSheetBundle {
+            sprite: TextureAtlasSprite {
+                index: sprites.square.index,
+                color: Sign::COLOR,
+                custom_size: Some(Vec2::new(4., 20.)),
+                ..default()
+            },
+            texture_atlas: sprites.square.atlas.clone_weak(),
+            transform: Transform::from_xyz(0., -22., 0.),
+            ..default()
+        });
+
+        builder.spawn((
+            SignPrompt,
+            Text2dBundle {
+                text: Text::from_section("", TextStyle {
+                    font,
+                    font_size: 16.,
+                    color: Sign::PROMPT_COLOR,
+                }).with_alignment(TextAlignment::Center),
+                transform: Transform::from_xyz(0., 32., 1.),
+                visibility: Visibility::Hidden,
+                ..default()
+            },
+        ));
+    });
+}
+
+/// Shows the prompt of the sign in range, and opens, presses or closes its [`SignBox`].
+pub fn update_sign_sys(
+    mut commands: Commands,
+    context: Res<RapierContext>,
+    settings: Res<Settings>, lang: Lang,
+    cix: Query<(Entity, &CixActState)>,
+    signs: Query<(Entity, &Sign, &Children)>,
+    mut prompts: Query<(&mut Text, &mut Visibility), With<SignPrompt>>,
+    boxes: Query<(Entity, &SignBox)>,
+    mut presses: EventWriter<TypewriterPressEvent>,
+) {
+    let near = cix.get_single().ok().and_then(|(cix, input)| signs.iter()
+        .find(|&(e, ..)| context.intersection_pair(cix, e) == Some(true))
+        .map(|(e, sign, _)| (e, sign, input))
+    );
+
+    // Walking away, dying or leaving the level closes the box.
+    let mut open = boxes.get_single().ok();
+    if let Some((e, &sign_box)) = open && near.map(|(e, ..)| e) != Some(sign_box.sign) {
+        commands.entity(e).despawn_recursive();
+        open = None;
+    }
+
+    let key = CixBinding::Interact.describe(&settings.bindings.cix, &lang).into_iter().next()
+        .unwrap_or_else(|| lang.text(CixBinding::Interact.key()).into());
+
+    for (e, _, children) in &signs {
+        let shown = open.is_none() && near.map(|(near, ..)| near) == Some(e);
+        for &child in children {
+            let Ok((mut text, mut visibility)) = prompts.get_mut(child) else { continue };
+
+            let target = if shown { Visibility::Inherited } else { Visibility::Hidden };
+            if *visibility != target {
+                *visibility = target;
+            }
+
+            if shown && text.sections[0].value != key {
+                text.sections[0].value = key.clone();
+            }
+
+            // The locale may have changed since the level spawned.
+            let font = lang.font();
+            if shown && text.sections[0].style.font != font {
+                text.sections[0].style.font = font;
+            }
+        }
+    }
+
+    let Some((sign_entity, sign, input)) = near else { return };
+    if !input.just_pressed(CixAction::Interact) { return };
+
+    if let Some((_, &sign_box)) = open {
+        presses.send(TypewriterPressEvent { entity: sign_box.text });
+        return;
+    }
+
+    let text = commands.spawn((
+        Typewriter::new(&sign.text, TextStyle {
+            font: lang.font(),
+            font_size: 24.,
+            color: Color::WHITE,
+        }).with_skippable(false),
+        TextBundle::default(),
+    )).id();
+
+    commands.spawn((
+        WorldObject,
+        SignBox { sign: sign_entity, text, },
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Percent(20.),
+                    right: Val::Percent(20.),
+                    bottom: Val::Px(32.),
+                    ..default()
+                },
+                padding: UiRect::all(Val::Px(16.)),
+                ..default()
+            },
+            background_color: BackgroundColor(Color::rgba(0., 0., 0., 0.7)),
+            ..default()
+        },
+    )).add_child(text);
+}
+
+pub fn update_sign_box_sys(
+    mut commands: Commands,
+    mut events: EventReader<TypewriterDoneEvent>,
+    boxes: Query<(Entity, &SignBox)>,
+) {
+    for &TypewriterDoneEvent { entity } in &mut events {
+        for (e, sign_box) in &boxes {
+            if sign_box.text == entity {
+                commands.entity(e).despawn_recursive();
+            }
+        }
+    }
+}